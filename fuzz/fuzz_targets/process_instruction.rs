@@ -0,0 +1,783 @@
+#![no_main]
+
+//! Runs sequences of arbitrary instructions through
+//! `entrypoint::process_instruction` against an in-memory account store.
+//!
+//! Every instruction gets its accounts serialized into the runtime's input
+//! layout and read back with `entrypoint::deserialize`, so `resize` and
+//! `assign` work as on chain. The syscall stubs provide the sysvars and stand
+//! in for the system program. Like the runtime, a failed instruction leaves
+//! the store untouched and a successful one is written back.
+//!
+//! Checked invariants: the program never panics, and a successful
+//! instruction leaves the total lamports of its accounts unchanged.
+
+use std::{
+    mem::size_of,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Once, OnceLock,
+    },
+};
+
+use arbitrary::Arbitrary;
+use borsh::BorshSerialize;
+use digital_sol_game::{
+    constants::{BPF_LOADER_UPGRADEABLE_ID, SYSTEM_ID},
+    entrypoint::process_instruction,
+    instruction::{self, ProgramInstruction},
+    processor::{
+        create_game, init_config, participate_game, reveal, set_withdraw_authority,
+        transfer_admin, withdraw_fees,
+    },
+    state::{
+        config::{Config, LEVEL_COUNT},
+        game::{CommitmentScheme, GameLevel, GameRecord, GameStatus},
+        vault::{Vault, MAX_WITHDRAW_SIGNERS},
+    },
+    utils::{
+        find_game_key, get_commitment_hash, get_config_key, get_program_data_key,
+        get_vault_key,
+    },
+};
+use libfuzzer_sys::fuzz_target;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{
+        deserialize, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE,
+        NON_DUP_MARKER, SUCCESS,
+    },
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_system_interface::{error::SystemError, instruction::SystemInstruction};
+
+/// More than any instruction takes, duplicates included.
+const MAX_ACCOUNTS: usize = 12;
+const MAX_STEPS: usize = 8;
+/// Keeps the sum of all balances far from `u64::MAX`, as on chain.
+const MAX_LAMPORTS: u64 = 1 << 56;
+const WALLET_LAMPORTS: u64 = 1_000_000_000_000;
+const GAME_COUNT: u8 = 4;
+const USER_COUNT: u8 = 6;
+/// Pool index of the first game PDA.
+const FIRST_GAME: usize = 6;
+/// Pool index of the first user, the upgrade authority and default admin.
+const FIRST_USER: usize = FIRST_GAME + GAME_COUNT as usize;
+const GAME_MAX: u16 = 10;
+/// Reveal time of the initial config.
+const REVEAL_TIME: i64 = 6000;
+
+static NOW: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    now: i64,
+    /// whether the config and vault already exist
+    initialized: bool,
+    /// overrides the default state of pool accounts
+    accounts: Vec<(u8, AccountState)>,
+    steps: Vec<Step>,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Step {
+    Raw { data: Vec<u8>, accounts: Vec<MetaInput> },
+    Encoded { instruction: FuzzInstruction, versioned: bool, accounts: Vec<MetaInput> },
+    /// Accounts come from the `instruction` builders, `keys` pick the users.
+    Built { instruction: FuzzInstruction, keys: [u8; 4] },
+    Wait(u32),
+}
+
+#[derive(Arbitrary, Debug)]
+struct MetaInput {
+    key: u8,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+struct AccountState {
+    owner: OwnerInput,
+    lamports: u64,
+    data: DataInput,
+}
+
+#[derive(Arbitrary, Debug)]
+enum OwnerInput {
+    Program,
+    System,
+    Loader,
+    Key(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+enum DataInput {
+    Empty,
+    Raw(Vec<u8>),
+    GameV1(Vec<u8>),
+    Game(GameInput),
+    Config(ConfigInput),
+    Vault(VaultInput),
+    ProgramData(Option<u8>),
+}
+
+#[derive(Arbitrary, Debug)]
+struct GameInput {
+    promoter: u8,
+    player: u8,
+    firing_point: u16,
+    max: u16,
+    odds_x100: u32,
+    level: u8,
+    shot_time: i64,
+    answer: u16,
+    random_string: [u8; 6],
+    status: u8,
+    salted: bool,
+    bet: u64,
+    reveal_time: i64,
+    reveal_deadline: i64,
+    revealed_at: i64,
+    open_until: i64,
+    bump: u8,
+    fee_bps: u16,
+}
+
+#[derive(Arbitrary, Debug)]
+struct ConfigInput {
+    admin: u8,
+    /// `None` is the vault PDA
+    vault: Option<u8>,
+    params: ConfigParams,
+}
+
+#[derive(Arbitrary, Debug)]
+struct ConfigParams {
+    fee_bps: u16,
+    reveal_time: i64,
+    min_reveal_time: i64,
+    max_reveal_time: i64,
+    enabled_levels: u8,
+    bets: [u64; LEVEL_COUNT],
+    crank_reward_bps: u16,
+    settle_grace_time: i64,
+}
+
+#[derive(Arbitrary, Debug)]
+struct VaultInput {
+    threshold: u8,
+    signers: Vec<u8>,
+    fees_collected: u64,
+    fees_withdrawn: u64,
+}
+
+/// `ProgramInstruction` with keys and commitments drawn from the pool.
+#[derive(Arbitrary, Debug)]
+enum FuzzInstruction {
+    CreateGame { game: GameChoice, odds_x100: u32, game_level: u8, reveal_time: i64, open_until: i64 },
+    Participate { point: u16 },
+    Reveal { secret: SecretChoice },
+    End,
+    Delete,
+    Migrate,
+    InitConfig(ConfigParams),
+    UpdateConfig(ConfigParams),
+    TransferAdmin { new_admin: u8 },
+    WithdrawFees { amount: u64 },
+    SetWithdrawAuthority { threshold: u8, signers: Vec<u8> },
+    Reclaim,
+}
+
+#[derive(Arbitrary, Debug)]
+enum GameChoice {
+    Pool(u8),
+    Any { max_number: u16, splicing_hash: [u8; 32] },
+}
+
+#[derive(Arbitrary, Debug)]
+enum SecretChoice {
+    Pool(u8),
+    Any { x: u16, legacy: Option<[u8; 6]>, salt: [u8; 32] },
+}
+
+/// A game whose commitment is known, so it can be created and revealed.
+struct PoolGame {
+    max: u16,
+    answer: u16,
+    salt: [u8; 32],
+    splicing_hash: [u8; 32],
+}
+
+/// Addresses the program cares about, so arbitrary inputs hit its PDAs.
+struct Pool {
+    keys: Vec<Pubkey>,
+    games: Vec<PoolGame>,
+}
+
+impl Pool {
+    fn index(&self, choice: u8) -> usize {
+        choice as usize % self.keys.len()
+    }
+
+    fn key(&self, choice: u8) -> Pubkey {
+        self.keys[self.index(choice)]
+    }
+
+    fn game(&self, choice: u8) -> &PoolGame {
+        &self.games[choice as usize % self.games.len()]
+    }
+
+    fn game_key(&self, choice: u8) -> Pubkey {
+        self.keys[FIRST_GAME + (choice % GAME_COUNT) as usize]
+    }
+
+    fn user(&self, choice: u8) -> Pubkey {
+        self.keys[FIRST_USER + (choice % USER_COUNT) as usize]
+    }
+}
+
+fn pool() -> &'static Pool {
+    static POOL: OnceLock<Pool> = OnceLock::new();
+
+    POOL.get_or_init(|| {
+        let users: Vec<Pubkey> = (0..USER_COUNT).map(|i| Pubkey::new_from_array([0x80 + i; 32])).collect();
+        // game `i` is promoted by user `i`
+        let games: Vec<PoolGame> = (0..GAME_COUNT)
+            .map(|i| {
+                let (answer, salt) = (i as u16 + 1, [i; 32]);
+                PoolGame {
+                    max: GAME_MAX,
+                    answer,
+                    salt,
+                    splicing_hash: get_commitment_hash(&users[i as usize], GAME_MAX, answer, &salt),
+                }
+            })
+            .collect();
+
+        let mut keys = vec![
+            digital_sol_game::ID,
+            SYSTEM_ID,
+            BPF_LOADER_UPGRADEABLE_ID,
+            get_config_key().0,
+            get_vault_key().0,
+            get_program_data_key().0,
+        ];
+        keys.extend(games.iter().map(|game| find_game_key(&game.splicing_hash).0));
+        keys.extend(users);
+
+        Pool { keys, games }
+    })
+}
+
+impl ConfigParams {
+    fn into_params(self) -> init_config::Params {
+        init_config::Params {
+            fee_bps: self.fee_bps,
+            reveal_time: self.reveal_time,
+            min_reveal_time: self.min_reveal_time,
+            max_reveal_time: self.max_reveal_time,
+            crank_reward_bps: self.crank_reward_bps,
+            settle_grace_time: self.settle_grace_time,
+            enabled_levels: self.enabled_levels,
+            bets: self.bets,
+        }
+    }
+}
+
+impl FuzzInstruction {
+    fn into_instruction(self, pool: &Pool) -> ProgramInstruction {
+        match self {
+            FuzzInstruction::CreateGame { game, odds_x100, game_level, reveal_time, open_until } => {
+                let (max_number, splicing_hash) = match game {
+                    GameChoice::Pool(choice) => {
+                        let game = pool.game(choice);
+                        (game.max, game.splicing_hash)
+                    }
+                    GameChoice::Any { max_number, splicing_hash } => (max_number, splicing_hash),
+                };
+                ProgramInstruction::CreateGame(create_game::Params {
+                    max_number,
+                    odds_x100,
+                    splicing_hash,
+                    game_level,
+                    reveal_time,
+                    open_until,
+                })
+            }
+            FuzzInstruction::Participate { point } => {
+                ProgramInstruction::Participate(participate_game::Params { point })
+            }
+            FuzzInstruction::Reveal { secret } => {
+                let params = match secret {
+                    SecretChoice::Pool(choice) => {
+                        let game = pool.game(choice);
+                        reveal::Params { x: game.answer, secret: reveal::Secret::Salted { salt: game.salt } }
+                    }
+                    SecretChoice::Any { x, legacy: Some(random), .. } => {
+                        reveal::Params { x, secret: reveal::Secret::Legacy { random } }
+                    }
+                    SecretChoice::Any { x, legacy: None, salt } => {
+                        reveal::Params { x, secret: reveal::Secret::Salted { salt } }
+                    }
+                };
+                ProgramInstruction::Reveal(params)
+            }
+            FuzzInstruction::End => ProgramInstruction::End,
+            FuzzInstruction::Delete => ProgramInstruction::Delete,
+            FuzzInstruction::Migrate => ProgramInstruction::Migrate,
+            FuzzInstruction::InitConfig(params) => ProgramInstruction::InitConfig(params.into_params()),
+            FuzzInstruction::UpdateConfig(params) => ProgramInstruction::UpdateConfig(params.into_params()),
+            FuzzInstruction::TransferAdmin { new_admin } => {
+                ProgramInstruction::TransferAdmin(transfer_admin::Params { new_admin: pool.key(new_admin) })
+            }
+            FuzzInstruction::WithdrawFees { amount } => {
+                ProgramInstruction::WithdrawFees(withdraw_fees::Params { amount })
+            }
+            FuzzInstruction::SetWithdrawAuthority { threshold, signers } => {
+                ProgramInstruction::SetWithdrawAuthority(set_withdraw_authority::Params {
+                    threshold,
+                    signers: signers.into_iter().map(|choice| pool.key(choice)).collect(),
+                })
+            }
+            FuzzInstruction::Reclaim => ProgramInstruction::Reclaim,
+        }
+    }
+}
+
+/// The program's own client for `instruction`, `keys` pick three users and a game.
+fn build(pool: &Pool, instruction: ProgramInstruction, keys: [u8; 4]) -> Instruction {
+    let [a, b, c, game] = keys;
+    let (a, b, c, game) = (pool.user(a), pool.user(b), pool.user(c), pool.game_key(game));
+
+    match instruction {
+        ProgramInstruction::CreateGame(params) => instruction::create_game(&a, params),
+        ProgramInstruction::Participate(params) => instruction::participate(&a, &b, &game, params.point),
+        ProgramInstruction::Reveal(params) => instruction::reveal(&a, &game, params),
+        ProgramInstruction::End => instruction::end(&a, &b, &c, &game),
+        ProgramInstruction::Delete => instruction::delete(&a, &game),
+        ProgramInstruction::Migrate => instruction::migrate(&a, &game),
+        ProgramInstruction::InitConfig(params) => instruction::init_config(&a, params),
+        ProgramInstruction::UpdateConfig(params) => instruction::update_config(&a, params),
+        ProgramInstruction::TransferAdmin(params) => instruction::transfer_admin(&a, &params.new_admin),
+        ProgramInstruction::WithdrawFees(params) => instruction::withdraw_fees(&a, &[b, c], params.amount),
+        ProgramInstruction::SetWithdrawAuthority(params) => instruction::set_withdraw_authority(&[a, b], params),
+        ProgramInstruction::Reclaim => instruction::reclaim(&a, &game),
+    }
+}
+
+impl Step {
+    /// The instruction data and account metas of the step, `None` for `Wait`.
+    fn into_parts(self, pool: &Pool) -> Option<(Vec<u8>, Vec<AccountMeta>)> {
+        let listed = |accounts: Vec<MetaInput>| {
+            accounts
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: pool.key(meta.key),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect()
+        };
+
+        match self {
+            Step::Raw { data, accounts } => Some((data, listed(accounts))),
+            Step::Encoded { instruction, versioned: true, accounts } => {
+                Some((instruction.into_instruction(pool).pack(), listed(accounts)))
+            }
+            Step::Encoded { instruction, versioned: false, accounts } => {
+                Some((instruction.into_instruction(pool).try_to_vec().unwrap(), listed(accounts)))
+            }
+            Step::Built { instruction, keys } => {
+                let built = build(pool, instruction.into_instruction(pool), keys);
+                Some((built.data, built.accounts))
+            }
+            Step::Wait(seconds) => {
+                NOW.fetch_add(seconds as i64, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+}
+
+impl DataInput {
+    fn into_data(self, pool: &Pool) -> Vec<u8> {
+        match self {
+            DataInput::Empty => vec![],
+            DataInput::Raw(data) => data,
+            DataInput::GameV1(mut data) => {
+                data.resize(GameRecord::V1_LEN, 0);
+                data
+            }
+            DataInput::Game(game) => {
+                let record = GameRecord {
+                    promoter: pool.key(game.promoter),
+                    player: pool.key(game.player),
+                    firing_point: game.firing_point,
+                    max: game.max,
+                    odds_x100: game.odds_x100,
+                    level: GameLevel::from_u8(&(game.level % 5)).unwrap(),
+                    shot_time: game.shot_time,
+                    answer: game.answer,
+                    random_string: game.random_string,
+                    status: GameStatus::from_u8(&(game.status % 6)).unwrap(),
+                    commitment: if game.salted { CommitmentScheme::Salted } else { CommitmentScheme::Legacy },
+                    bet: game.bet,
+                    reveal_time: game.reveal_time,
+                    reveal_deadline: game.reveal_deadline,
+                    revealed_at: game.revealed_at,
+                    open_until: game.open_until,
+                    bump: game.bump,
+                    fee_bps: game.fee_bps,
+                };
+                let mut data = vec![0; GameRecord::LEN];
+                record.pack_into_slice(&mut data);
+                data
+            }
+            DataInput::Config(config) => {
+                let params = config.params;
+                let config = Config {
+                    admin: pool.key(config.admin),
+                    vault: config.vault.map_or(get_vault_key().0, |choice| pool.key(choice)),
+                    fee_bps: params.fee_bps,
+                    reveal_time: params.reveal_time,
+                    min_reveal_time: params.min_reveal_time,
+                    max_reveal_time: params.max_reveal_time,
+                    crank_reward_bps: params.crank_reward_bps,
+                    settle_grace_time: params.settle_grace_time,
+                    enabled_levels: params.enabled_levels,
+                    bets: params.bets,
+                };
+                let mut data = vec![0; Config::LEN];
+                config.pack_into_slice(&mut data);
+                data
+            }
+            DataInput::Vault(vault) => {
+                let mut signers = [Pubkey::default(); MAX_WITHDRAW_SIGNERS];
+                for (signer, choice) in signers.iter_mut().zip(&vault.signers) {
+                    *signer = pool.key(*choice);
+                }
+                let vault = Vault {
+                    threshold: vault.threshold,
+                    signers,
+                    signer_count: vault.signers.len().min(MAX_WITHDRAW_SIGNERS) as u8,
+                    fees_collected: vault.fees_collected,
+                    fees_withdrawn: vault.fees_withdrawn,
+                };
+                let mut data = vec![0; Vault::LEN];
+                vault.pack_into_slice(&mut data);
+                data
+            }
+            DataInput::ProgramData(authority) => program_data(authority.map(|choice| pool.key(choice))),
+        }
+    }
+}
+
+/// bincode of `UpgradeableLoaderState::ProgramData`
+fn program_data(authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(authority.as_ref());
+        }
+        None => data.push(0),
+    }
+    data
+}
+
+struct StoredAccount {
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+impl StoredAccount {
+    fn empty(owner: Pubkey, lamports: u64) -> Self {
+        Self { owner, lamports, data: vec![] }
+    }
+
+    fn program_owned(data: Vec<u8>) -> Self {
+        Self { owner: digital_sol_game::ID, lamports: Rent::default().minimum_balance(data.len()), data }
+    }
+}
+
+/// Every account the instructions have seen, starting with the pool's.
+struct Store {
+    keys: Vec<Pubkey>,
+    accounts: Vec<StoredAccount>,
+}
+
+impl Store {
+    /// A deployed program whose upgrade authority is the first user, with the
+    /// default config when `initialized`.
+    fn new(pool: &Pool, initialized: bool) -> Self {
+        let admin = pool.keys[FIRST_USER];
+
+        let mut accounts = vec![
+            StoredAccount::empty(BPF_LOADER_UPGRADEABLE_ID, 1),
+            StoredAccount::empty(SYSTEM_ID, 1),
+            StoredAccount::empty(SYSTEM_ID, 1),
+        ];
+
+        if initialized {
+            let config = init_config::Params {
+                fee_bps: 100,
+                reveal_time: REVEAL_TIME,
+                min_reveal_time: 60,
+                max_reveal_time: REVEAL_TIME * 10,
+                crank_reward_bps: 1_000,
+                settle_grace_time: 600,
+                enabled_levels: 0b11111,
+                bets: [GameLevel::S, GameLevel::A, GameLevel::B, GameLevel::C, GameLevel::D]
+                    .map(|level| level.get_bet()),
+            }
+            .into_config(admin);
+            let mut data = vec![0; Config::LEN];
+            config.pack_into_slice(&mut data);
+            accounts.push(StoredAccount::program_owned(data));
+
+            let mut data = vec![0; Vault::LEN];
+            Vault::new(&[admin], 1).unwrap().pack_into_slice(&mut data);
+            accounts.push(StoredAccount::program_owned(data));
+        } else {
+            accounts.push(StoredAccount::empty(SYSTEM_ID, 0));
+            accounts.push(StoredAccount::empty(SYSTEM_ID, 0));
+        }
+
+        let mut program_data = StoredAccount::program_owned(program_data(Some(admin)));
+        program_data.owner = BPF_LOADER_UPGRADEABLE_ID;
+        accounts.push(program_data);
+
+        accounts.extend((0..GAME_COUNT).map(|_| StoredAccount::empty(SYSTEM_ID, 0)));
+        accounts.extend((0..USER_COUNT).map(|_| StoredAccount::empty(SYSTEM_ID, WALLET_LAMPORTS)));
+
+        Self { keys: pool.keys.clone(), accounts }
+    }
+
+    /// Where `key` is stored, an unknown address is an empty account.
+    fn index(&mut self, key: &Pubkey) -> usize {
+        match self.keys.iter().position(|k| k == key) {
+            Some(index) => index,
+            None => {
+                self.keys.push(*key);
+                self.accounts.push(StoredAccount::empty(SYSTEM_ID, 0));
+                self.keys.len() - 1
+            }
+        }
+    }
+
+    fn set(&mut self, pool: &Pool, choice: u8, state: AccountState) {
+        let owner = match state.owner {
+            OwnerInput::Program => digital_sol_game::ID,
+            OwnerInput::System => SYSTEM_ID,
+            OwnerInput::Loader => BPF_LOADER_UPGRADEABLE_ID,
+            OwnerInput::Key(choice) => pool.key(choice),
+        };
+        self.accounts[pool.index(choice)] = StoredAccount {
+            owner,
+            lamports: state.lamports % MAX_LAMPORTS,
+            data: state.data.into_data(pool),
+        };
+    }
+
+    fn total_lamports(&self) -> u128 {
+        self.accounts.iter().map(|account| account.lamports as u128).sum()
+    }
+
+    /// Runs one instruction, the store only changes if it succeeds.
+    fn execute(&mut self, instruction_data: &[u8], metas: &[AccountMeta]) {
+        let indexes: Vec<usize> = metas.iter().map(|meta| self.index(&meta.pubkey)).collect();
+        let (mut buffer, unique) = self.serialize(metas, &indexes, instruction_data);
+
+        let (program_id, infos, instruction_data) = unsafe { deserialize(buffer.as_mut_ptr() as *mut u8) };
+        let before = total_lamports(&infos, &unique);
+
+        if process_instruction(program_id, &infos, instruction_data).is_err() {
+            return;
+        }
+        assert_eq!(total_lamports(&infos, &unique), before, "lamports were created or destroyed");
+
+        for position in unique {
+            let info = &infos[position];
+            self.accounts[indexes[position]] = StoredAccount {
+                owner: *info.owner,
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+            };
+        }
+    }
+
+    /// Writes the accounts the way the runtime does before calling a program,
+    /// returns the buffer and the position of every distinct account.
+    fn serialize(
+        &self,
+        metas: &[AccountMeta],
+        indexes: &[usize],
+        instruction_data: &[u8],
+    ) -> (Vec<u128>, Vec<usize>) {
+        let mut input = Vec::new();
+        let mut unique = Vec::new();
+
+        input.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        for (position, (meta, index)) in metas.iter().zip(indexes).enumerate() {
+            if let Some(original) = indexes[..position].iter().position(|i| i == index) {
+                input.push(original as u8);
+                input.extend_from_slice(&[0; 7]);
+                continue;
+            }
+            unique.push(position);
+
+            let (key, account) = (&self.keys[*index], &self.accounts[*index]);
+            input.push(NON_DUP_MARKER);
+            input.push(meta.is_signer as u8);
+            input.push(meta.is_writable as u8);
+            input.push((key == &digital_sol_game::ID) as u8); // executable
+            input.extend_from_slice(&[0; 4]); // original data length, set by `deserialize`
+            input.extend_from_slice(key.as_ref());
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+            input.extend_from_slice(&0u64.to_le_bytes()); // rent epoch
+        }
+
+        input.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+        input.extend_from_slice(instruction_data);
+        input.extend_from_slice(digital_sol_game::ID.as_ref());
+
+        // `deserialize` reads u64s in place, the buffer has to be aligned
+        let mut buffer = vec![0u128; input.len().div_ceil(size_of::<u128>())];
+        unsafe {
+            std::ptr::copy_nonoverlapping(input.as_ptr(), buffer.as_mut_ptr() as *mut u8, input.len());
+        }
+
+        (buffer, unique)
+    }
+}
+
+fn total_lamports(accounts: &[AccountInfo], unique: &[usize]) -> u128 {
+    unique.iter().map(|position| accounts[*position].lamports() as u128).sum()
+}
+
+/// Stands in for the runtime's sysvars and the system program.
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock { unix_timestamp: NOW.load(Ordering::Relaxed), ..Clock::default() };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        system_program(instruction, account_infos, signers_seeds)
+    }
+}
+
+/// The `CreateAccount` and `Transfer` handling of the system program,
+/// including the privilege checks of a cross-program invocation.
+fn system_program(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if instruction.program_id != SYSTEM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let signed_by_program = |key: &Pubkey| {
+        signers_seeds
+            .iter()
+            .any(|seeds| Pubkey::create_program_address(seeds, &digital_sol_game::ID).as_ref() == Ok(key))
+    };
+    let account = |index: usize| -> Result<&AccountInfo, ProgramError> {
+        let meta = instruction.accounts.get(index).ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let info = account_infos
+            .iter()
+            .find(|info| info.key == &meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_writable && !info.is_writable {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if meta.is_signer && !info.is_signer && !signed_by_program(info.key) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Ok(info)
+    };
+
+    match bincode::deserialize(&instruction.data).map_err(|_| ProgramError::InvalidInstructionData)? {
+        SystemInstruction::CreateAccount { lamports, space, owner } => {
+            let (from, to) = (account(0)?, account(1)?);
+            if to.lamports() > 0 || !to.data_is_empty() || to.owner != &SYSTEM_ID {
+                return Err(ProgramError::Custom(SystemError::AccountAlreadyInUse as u32));
+            }
+            transfer(from, to, lamports)?;
+            to.resize(space as usize)?;
+            to.assign(&owner);
+            Ok(())
+        }
+        SystemInstruction::Transfer { lamports } => transfer(account(0)?, account(1)?, lamports),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if from.owner != &SYSTEM_ID || !from.data_is_empty() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if from.lamports() < lamports {
+        return Err(ProgramError::Custom(SystemError::ResultWithNegativeLamports as u32));
+    }
+
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fuzz_target!(|input: Input| {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+    NOW.store(input.now, Ordering::Relaxed);
+
+    let pool = pool();
+    let mut store = Store::new(pool, input.initialized);
+    for (choice, state) in input.accounts {
+        store.set(pool, choice, state);
+    }
+    let total = store.total_lamports();
+
+    for step in input.steps.into_iter().take(MAX_STEPS) {
+        let Some((instruction_data, mut metas)) = step.into_parts(pool) else {
+            continue;
+        };
+        metas.truncate(MAX_ACCOUNTS);
+        store.execute(&instruction_data, &metas);
+    }
+
+    assert_eq!(store.total_lamports(), total, "lamports were created or destroyed");
+});
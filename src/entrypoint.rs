@@ -1,14 +1,14 @@
 
 //solana create  --version 1.18.11
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey,
 };
 //custom create
 //Conditional compilation
 #[cfg(not(feature = "no-entrypoint"))]
 use solana_program::entrypoint;
 
-use crate::processor::Processor;
+use crate::{msg, processor::Processor};
 #[cfg(not(feature = "no-entrypoint"))]
 
 
@@ -0,0 +1,97 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+/// Errors returned by the game program.
+///
+/// Every variant is surfaced on chain as `ProgramError::Custom(code)`.
+/// The discriminants are part of the public interface: never reorder or
+/// reuse them, only append new variants.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum GameError {
+    #[error("The game account does not match the given commitment")]
+    InvalidGameAccount = 0,
+    #[error("Unknown game level")]
+    InvalidGameLevel = 1,
+    #[error("The guessed point must be between 1 and the game's max")]
+    InvalidPoint = 2,
+    #[error("Not your game")]
+    NotPromoter = 3,
+    #[error("The given promoter does not match the game")]
+    PromoterMismatch = 4,
+    #[error("The given player does not match the game")]
+    PlayerMismatch = 5,
+    #[error("The game hasn't started")]
+    GameNotStarted = 6,
+    #[error("The game has already started")]
+    GameAlreadyStarted = 7,
+    #[error("Over the reveal time")]
+    RevealTimeExpired = 8,
+    #[error("Settlement cannot be made before reveal")]
+    RevealPending = 9,
+    #[error("Only the promoter or the player can settle the game")]
+    InvalidSettler = 10,
+    #[error("The game has already been settled or cancelled")]
+    GameFinished = 11,
+    #[error("The answer has already been revealed")]
+    AlreadyRevealed = 12,
+    #[error("Illegal game status transition")]
+    InvalidStatusTransition = 13,
+    #[error("The account is not of the expected type")]
+    WrongAccountType = 14,
+    #[error("The account layout version is not supported")]
+    UnsupportedAccountVersion = 15,
+    #[error("The game record uses the old layout and must be migrated")]
+    AccountNeedsMigration = 16,
+    #[error("The game escrow can't cover the payout")]
+    InsufficientEscrow = 17,
+    #[error("The promoter can't join their own game")]
+    PromoterCannotPlay = 18,
+    #[error("The revealed secret doesn't use the game's commitment scheme")]
+    WrongCommitmentScheme = 19,
+    #[error("Only the config admin can do this")]
+    Unauthorized = 20,
+    #[error("This game level is disabled")]
+    LevelDisabled = 21,
+    #[error("Invalid config settings")]
+    InvalidConfig = 22,
+    #[error("Invalid withdraw authority")]
+    InvalidWithdrawAuthority = 23,
+    #[error("Not enough withdraw authority signatures")]
+    InsufficientSigners = 24,
+    #[error("The vault can't cover the withdrawal")]
+    InsufficientVaultBalance = 25,
+    #[error("Arithmetic overflow")]
+    MathOverflow = 26,
+    #[error("Game is closed")]
+    GameClosed = 27,
+    #[error("Reveal time is out of the config bounds")]
+    InvalidRevealTime = 28,
+    #[error("Game is no longer open to join")]
+    GameExpired = 29,
+    #[error("Game is still open to join")]
+    GameNotExpired = 30,
+}
+
+impl GameError {
+    /// Maps a `ProgramError::Custom` code back to the game error it came from.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::from_u32(code)
+    }
+
+    /// Decodes a program error returned by this program, if it is one of ours.
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+}
+
+impl From<GameError> for ProgramError {
+    fn from(e: GameError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
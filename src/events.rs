@@ -0,0 +1,111 @@
+//! Events the processors emit with `sol_log_data`, for indexers.
+//!
+//! Every event is logged as two fields, `EVENT_TAG` and the borsh encoded
+//! `GameEvent`, which show up in the transaction logs as
+//! `Program data: <base64 tag> <base64 event>`. `decode_logs` turns those
+//! lines back into events and skips everything else.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::settlement::Outcome;
+
+/// First field of every event log, tells our events apart from other data
+pub const EVENT_TAG: &[u8] = b"digital_bomb:event";
+
+/// Prefix the runtime puts before the base64 fields of `sol_log_data`
+const DATA_LOG_PREFIX: &str = "Program data: ";
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct GameCreated {
+    pub game: Pubkey,
+    pub promoter: Pubkey,
+    pub max: u16,
+    pub odds_x100: u32,
+    pub level: u8,
+    pub bet: u64,
+    /// lamports locked by the promoter, rent included
+    pub collateral: u64,
+    pub reveal_time: i64,
+    pub open_until: i64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct GameJoined {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub point: u16,
+    pub stake: u64,
+    pub reveal_deadline: i64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct GameRevealed {
+    pub game: Pubkey,
+    pub answer: u16,
+    /// the answer is out of `1..=max`, the promoter loses the escrow
+    pub forfeited: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct GameSettled {
+    pub game: Pubkey,
+    pub settler: Pubkey,
+    pub outcome: Outcome,
+    pub to_player: u64,
+    /// the promoter's share of the escrow, `rent` excluded
+    pub to_promoter: u64,
+    /// the fee kept by the vault, `crank_reward` excluded
+    pub to_vault: u64,
+    pub crank_reward: u64,
+    /// the rent exemption of the closed game account, back to the promoter
+    pub rent: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub struct GameDeleted {
+    pub game: Pubkey,
+    pub promoter: Pubkey,
+    /// every lamport of the game account, back to the promoter
+    pub refund: u64,
+    /// closed by `Reclaim` after `open_until` rather than by the promoter
+    pub expired: bool,
+}
+
+/// The tag is the variant index, new variants only go at the end.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    GameCreated(GameCreated),
+    GameJoined(GameJoined),
+    GameRevealed(GameRevealed),
+    GameSettled(GameSettled),
+    GameDeleted(GameDeleted),
+}
+
+impl GameEvent {
+    /// Logs the event with `sol_log_data`.
+    pub fn emit(&self) {
+        sol_log_data(&[EVENT_TAG, &self.try_to_vec().unwrap()]);
+    }
+
+    /// Decodes one transaction log line, `None` if it isn't one of our events.
+    pub fn decode_log(line: &str) -> Option<Self> {
+        let mut fields = line.strip_prefix(DATA_LOG_PREFIX)?.split(' ');
+
+        if STANDARD.decode(fields.next()?).ok()? != EVENT_TAG {
+            return None;
+        }
+        let event = STANDARD.decode(fields.next()?).ok()?;
+        if fields.next().is_some() {
+            return None;
+        }
+
+        Self::try_from_slice(&event).ok()
+    }
+}
+
+/// Every event in the log lines of a transaction, in order.
+pub fn decode_logs<S: AsRef<str>>(logs: &[S]) -> Vec<GameEvent> {
+    logs.iter().filter_map(|line| GameEvent::decode_log(line.as_ref())).collect()
+}
@@ -1,15 +1,242 @@
 
 use borsh::{BorshDeserialize, BorshSerialize};
-use num_derive::FromPrimitive;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use web3_utils::accounts::InstructionsAccount;
 
+pub mod legacy;
 
+use crate::{
+    constants::SYSTEM_ID,
+    msg,
+    processor::{
+        create_game, delete, end, init_config, migrate, participate_game, reclaim, reveal,
+        set_withdraw_authority, transfer_admin, update_config, withdraw_fees,
+    },
+    utils::{find_game_key, get_config_key, get_program_data_key, get_vault_key},
+};
 
-#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, FromPrimitive)]
+/// First byte of a versioned instruction, never used as a variant tag
+pub const VERSION_PREFIX: u8 = 0xff;
+/// Instruction format written by `ProgramInstruction::pack`, bumped whenever
+/// a deployed params layout changes, the deployed ones keep being decoded.
+/// Version 1 was never deployed and isn't decoded.
+pub const INSTRUCTION_VERSION: u8 = 2;
+
+/// Instruction data is `VERSION_PREFIX | version | tag | borsh params`, or
+/// the original program's `tag | borsh params`, see `legacy`.
+///
+/// The tag is the variant index, new variants only go at the end.
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
 pub enum ProgramInstruction {
-    CreateGame,
-    Participate,
-    Reveal,
+    CreateGame(create_game::Params),
+    Participate(participate_game::Params),
+    Reveal(reveal::Params),
     End,
-    Delete
+    Delete,
+    Migrate,
+    InitConfig(init_config::Params),
+    UpdateConfig(update_config::Params),
+    TransferAdmin(transfer_admin::Params),
+    WithdrawFees(withdraw_fees::Params),
+    SetWithdrawAuthority(set_withdraw_authority::Params),
+    Reclaim,
+}
+
+impl ProgramInstruction {
+    /// Decodes the whole instruction data, trailing bytes are an error.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        match data {
+            [] => {
+                msg!("Empty instruction data");
+                Err(ProgramError::InvalidInstructionData)
+            }
+            [VERSION_PREFIX, INSTRUCTION_VERSION, rest @ ..] => decode(rest),
+            [VERSION_PREFIX, version, ..] => {
+                msg!("Unsupported instruction version {}", version);
+                Err(ProgramError::InvalidInstructionData)
+            }
+            [VERSION_PREFIX] => {
+                msg!("Missing instruction version");
+                Err(ProgramError::InvalidInstructionData)
+            }
+            _ => decode::<legacy::ProgramInstruction>(data).map(Self::from),
+        }
+    }
+
+    /// Encodes the instruction with the current version prefix.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![VERSION_PREFIX, INSTRUCTION_VERSION];
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    fn into_instruction(self, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts,
+            data: self.pack(),
+        }
+    }
+}
+
+/// Borsh decodes the whole of `data`.
+fn decode<T: BorshDeserialize>(data: &[u8]) -> Result<T, ProgramError> {
+    T::try_from_slice(data).map_err(|err| {
+        msg!("Invalid instruction data: {}", err);
+        ProgramError::InvalidInstructionData
+    })
+}
+
+/// The game PDA committed to by `splicing_hash`.
+pub fn get_game_key(splicing_hash: &[u8; 32]) -> Pubkey {
+    find_game_key(splicing_hash).0
+}
+
+/// Creates the game committed to by `params.splicing_hash`.
+pub fn create_game(promoter: &Pubkey, params: create_game::Params) -> Instruction {
+    let accounts = create_game::Accounts {
+        promoter,
+        game_recorder: &get_game_key(&params.splicing_hash),
+        system_program: &SYSTEM_ID,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::CreateGame(params).into_instruction(accounts)
+}
+
+pub fn participate(player: &Pubkey, promoter: &Pubkey, game: &Pubkey, point: u16) -> Instruction {
+    let accounts = participate_game::Accounts {
+        player,
+        game_recorder: game,
+        system_program: &SYSTEM_ID,
+        promoter,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::Participate(participate_game::Params { point }).into_instruction(accounts)
+}
+
+pub fn reveal(promoter: &Pubkey, game: &Pubkey, params: reveal::Params) -> Instruction {
+    let accounts = reveal::Accounts {
+        promoter,
+        game_recorder: game,
+        system_program: &SYSTEM_ID,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::Reveal(params).into_instruction(accounts)
+}
+
+/// Settles a game, `terminator` is its promoter or its player, or anyone
+/// once the game is open to settle, who then earns the crank reward.
+pub fn end(terminator: &Pubkey, promoter: &Pubkey, player: &Pubkey, game: &Pubkey) -> Instruction {
+    let accounts = end::Accounts {
+        terminator,
+        promoter,
+        player,
+        game_recorder: game,
+        vault: &get_vault_key().0,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::End.into_instruction(accounts)
+}
+
+pub fn delete(promoter: &Pubkey, game: &Pubkey) -> Instruction {
+    let accounts = delete::Accounts {
+        promoter,
+        game_recorder: game,
+        system_program: &SYSTEM_ID,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::Delete.into_instruction(accounts)
+}
+
+pub fn migrate(payer: &Pubkey, game: &Pubkey) -> Instruction {
+    let accounts = migrate::Accounts {
+        payer,
+        game_recorder: game,
+        system_program: &SYSTEM_ID,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::Migrate.into_instruction(accounts)
+}
+
+/// Must be signed by the program's upgrade authority.
+pub fn init_config(admin: &Pubkey, params: init_config::Params) -> Instruction {
+    let accounts = init_config::Accounts {
+        admin,
+        config: &get_config_key().0,
+        vault: &get_vault_key().0,
+        program_data: &get_program_data_key().0,
+        system_program: &SYSTEM_ID,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::InitConfig(params).into_instruction(accounts)
+}
+
+pub fn update_config(admin: &Pubkey, params: update_config::Params) -> Instruction {
+    let accounts = update_config::Accounts {
+        admin,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::UpdateConfig(params).into_instruction(accounts)
 }
 
+pub fn transfer_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    let accounts = transfer_admin::Accounts {
+        admin,
+        config: &get_config_key().0,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::TransferAdmin(transfer_admin::Params { new_admin: *new_admin })
+        .into_instruction(accounts)
+}
+
+/// `signers` are the members of the withdraw authority signing the withdrawal.
+pub fn withdraw_fees(destination: &Pubkey, signers: &[Pubkey], amount: u64) -> Instruction {
+    let accounts = withdraw_fees::Accounts {
+        vault: &get_vault_key().0,
+        destination,
+        signers,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::WithdrawFees(withdraw_fees::Params { amount }).into_instruction(accounts)
+}
+
+/// Replaces the withdraw authority, `signers` are members of the current one.
+pub fn set_withdraw_authority(signers: &[Pubkey], params: set_withdraw_authority::Params) -> Instruction {
+    let accounts = set_withdraw_authority::Accounts {
+        vault: &get_vault_key().0,
+        signers,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::SetWithdrawAuthority(params).into_instruction(accounts)
+}
+
+/// Closes a game nobody joined before its `open_until`, anyone can send it.
+pub fn reclaim(promoter: &Pubkey, game: &Pubkey) -> Instruction {
+    let accounts = reclaim::Accounts {
+        promoter,
+        game_recorder: game,
+    }
+    .get_accounts_vec();
+
+    ProgramInstruction::Reclaim.into_instruction(accounts)
+}
@@ -0,0 +1,67 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use web3_utils::{BorshSize, borsh_size::BorshSize};
+
+use crate::processor::{create_game, participate_game, reveal};
+
+/// `create_game::Params` of the original program, before games had their own
+/// reveal time and expiry
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct CreateGameParams {
+    pub max_number: u16,
+    pub odds_x100: u32,
+    pub splicing_hash: [u8; 32],
+    pub game_level: u8,
+}
+
+/// `reveal::Params` of the original program, before salted commitments
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct RevealParams {
+    pub x: u16,
+    pub random: [u8; 6],
+}
+
+/// The unversioned `tag | params` instructions of the original program, the
+/// only layout deployed before `super::VERSION_PREFIX`.
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize)]
+pub enum ProgramInstruction {
+    CreateGame(CreateGameParams),
+    Participate(participate_game::Params),
+    Reveal(RevealParams),
+    End,
+    Delete,
+}
+
+impl From<CreateGameParams> for create_game::Params {
+    /// The config's reveal time and no expiry, as every original game.
+    fn from(params: CreateGameParams) -> Self {
+        Self {
+            max_number: params.max_number,
+            odds_x100: params.odds_x100,
+            splicing_hash: params.splicing_hash,
+            game_level: params.game_level,
+            reveal_time: 0,
+            open_until: 0,
+        }
+    }
+}
+
+impl From<RevealParams> for reveal::Params {
+    fn from(params: RevealParams) -> Self {
+        Self {
+            x: params.x,
+            secret: reveal::Secret::Legacy { random: params.random },
+        }
+    }
+}
+
+impl From<ProgramInstruction> for super::ProgramInstruction {
+    fn from(instruction: ProgramInstruction) -> Self {
+        match instruction {
+            ProgramInstruction::CreateGame(params) => Self::CreateGame(params.into()),
+            ProgramInstruction::Participate(params) => Self::Participate(params),
+            ProgramInstruction::Reveal(params) => Self::Reveal(params.into()),
+            ProgramInstruction::End => Self::End,
+            ProgramInstruction::Delete => Self::Delete,
+        }
+    }
+}
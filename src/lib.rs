@@ -3,10 +3,13 @@ use solana_program::declare_id;
 
 
 pub mod entrypoint;
+pub mod error;
 pub mod utils;
 pub mod state;
 pub mod instruction;
 pub mod processor;
+pub mod settlement;
+pub mod events;
 
 
 #[cfg(not(feature = "devnet"))]
@@ -20,10 +23,42 @@ pub mod constants {
     use solana_program::{pubkey, pubkey::Pubkey};
     pub const SYSTEM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
 
-    pub const VAULT: Pubkey = pubkey!("EYVjoX4t59WsHDoRMHySqHiG58zGbZCCieF5K9heLSc5");
+    pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
 
-    #[cfg(feature = "devnet")]
-    pub const REVEAL_TIME: i64 = 6000; // 10min
-    #[cfg(not(feature = "devnet"))]
-    pub const REVEAL_TIME: i64 = 600000; // 1000min
+    /// seed of the `Config` PDA
+    pub const CONFIG_SEED: &[u8] = b"config";
+
+    /// seed of a game PDA after its commitment, `hashv(&[b"game"])`
+    pub const GAME_SEED: [u8; 32] = [
+        0x6c, 0xa5, 0xca, 0xb7, 0x7e, 0x70, 0x2c, 0x78, 0x7b, 0x4c, 0x14, 0xb3, 0xd3, 0xbf, 0x26, 0xba,
+        0xd4, 0x3d, 0xa6, 0x06, 0xbe, 0x6e, 0xed, 0x04, 0xab, 0x0b, 0x97, 0x20, 0x12, 0x0a, 0xe0, 0x81,
+    ];
+
+    /// seed of the fee `Vault` PDA
+    pub const VAULT_SEED: &[u8] = b"vault";
+
+    /// the `Config` PDA of `crate::ID` and its bump, precomputed so
+    /// processors don't search it
+    pub const CONFIG_KEY: Pubkey = pubkey!("5aS4HRo2P8Yyyawqa14WBitxQarywYNy2KogntoaBvUu");
+    pub const CONFIG_BUMP: u8 = 255;
+
+    /// the `Vault` PDA of `crate::ID` and its bump
+    pub const VAULT_KEY: Pubkey = pubkey!("jn5A6YaG3UGX4PBSRagMqevU7irWdZYMemh4j6nsv89");
+    pub const VAULT_BUMP: u8 = 255;
+
+    /// domain separation tag of the salted commitment
+    pub const COMMITMENT_TAG: &[u8] = b"digital_bomb:commitment:v2";
+}
+
+/// `solana_program::msg!` unless the `no-logs` feature is on, the
+/// `events` are logged either way.
+macro_rules! msg {
+    ($($arg:tt)*) => {{
+        #[cfg(not(feature = "no-logs"))]
+        solana_program::msg!($($arg)*);
+        // never called, keeps the arguments used without formatting them
+        #[cfg(feature = "no-logs")]
+        let _ = || solana_program::msg!($($arg)*);
+    }};
 }
+pub(crate) use msg;
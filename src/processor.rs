@@ -1,9 +1,6 @@
-use crate::instruction::ProgramInstruction;
-use borsh::BorshDeserialize;
-use num_traits::FromPrimitive;
+use crate::{instruction::ProgramInstruction, msg};
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
-    pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey,
 };
 
 pub struct Processor {}
@@ -13,6 +10,13 @@ pub mod end;
 pub mod participate_game;
 pub mod reveal;
 pub mod delete;
+pub mod migrate;
+pub mod init_config;
+pub mod update_config;
+pub mod transfer_admin;
+pub mod withdraw_fees;
+pub mod set_withdraw_authority;
+pub mod reclaim;
 
 impl Processor {
     pub fn process_instruction(
@@ -23,29 +27,21 @@ impl Processor {
         msg!("Beginning processing");
         msg!("instruction: {:?}", instruction_data);
 
-        let instruction = FromPrimitive::from_u8(instruction_data[0])
-            .ok_or(ProgramError::InvalidInstructionData)?;
-        let instruction_data = &instruction_data[1..];
+        let instruction = ProgramInstruction::unpack(instruction_data)?;
 
         msg!("Instruction unpacked: means instruction data is ok");
 
         match instruction {
-            ProgramInstruction::CreateGame => {
+            ProgramInstruction::CreateGame(params) => {
                 msg!("Instruction: start a game");
-                let params = create_game::Params::try_from_slice(instruction_data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?;
                 create_game::process_create_game(program_id, accounts, params)?;
             }
-            ProgramInstruction::Participate => {
+            ProgramInstruction::Participate(params) => {
                 msg!("Instruction: Participate in a game");
-                let params = participate_game::Params::try_from_slice(instruction_data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?;
                 participate_game::process_participate_game(program_id, accounts, params)?;
             }
-            ProgramInstruction::Reveal => {
+            ProgramInstruction::Reveal(params) => {
                 msg!("Instruction: reveal a game");
-                let params = reveal::Params::try_from_slice(instruction_data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?;
                 reveal::process_reveal_game(program_id, accounts, params)?;
             }
             ProgramInstruction::End => {
@@ -55,6 +51,34 @@ impl Processor {
             ProgramInstruction::Delete => {
                 delete::process_delete_game(program_id, accounts)?;
             }
+            ProgramInstruction::Migrate => {
+                msg!("Instruction: migrate a game record");
+                migrate::process_migrate_game(program_id, accounts)?;
+            }
+            ProgramInstruction::InitConfig(params) => {
+                msg!("Instruction: init the config");
+                init_config::process_init_config(program_id, accounts, params)?;
+            }
+            ProgramInstruction::UpdateConfig(params) => {
+                msg!("Instruction: update the config");
+                update_config::process_update_config(program_id, accounts, params)?;
+            }
+            ProgramInstruction::TransferAdmin(params) => {
+                msg!("Instruction: transfer the config admin");
+                transfer_admin::process_transfer_admin(program_id, accounts, params)?;
+            }
+            ProgramInstruction::WithdrawFees(params) => {
+                msg!("Instruction: withdraw fees");
+                withdraw_fees::process_withdraw_fees(program_id, accounts, params)?;
+            }
+            ProgramInstruction::SetWithdrawAuthority(params) => {
+                msg!("Instruction: set the withdraw authority");
+                set_withdraw_authority::process_set_withdraw_authority(program_id, accounts, params)?;
+            }
+            ProgramInstruction::Reclaim => {
+                msg!("Instruction: reclaim an expired game");
+                reclaim::process_reclaim_game(program_id, accounts)?;
+            }
         }
 
         Ok(())
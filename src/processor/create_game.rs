@@ -4,25 +4,66 @@ use web3_utils::{
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info}, 
-    entrypoint::ProgramResult, msg, program::invoke_signed, 
-    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, 
+    entrypoint::ProgramResult, program::invoke_signed, 
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+    clock::Clock,
 };
 
 use solana_system_interface::instruction as system_instruction;
 
-use crate::{constants::SYSTEM_ID, state::game::{GameLevel, GameRecord}, utils::get_seeds_and_key};
+use crate::{
+    constants::SYSTEM_ID, error::GameError, events::{GameCreated, GameEvent}, msg, settlement::required_collateral,
+    state::{config::Config, game::{GameLevel, GameRecord}}, utils::{find_game_key, get_game_seeds, load_config},
+};
 
 
-#[derive(BorshDeserialize, BorshSerialize, BorshSize, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
 pub struct Params {
     // means the limitation of the game's answer
     pub max_number: u16,
     // the odds * 100
     pub odds_x100: u32,
-    // the calculated splicing hash value
+    // the commitment to the answer, see `utils::get_commitment_hash`
     pub splicing_hash: [u8; 32],
     // game level -- Determine how much SOL to bet
     pub game_level: u8,
+    // seconds the promoter has to reveal once a player joined, within the
+    // config bounds, 0 for the config's reveal time
+    pub reveal_time: i64,
+    // last timestamp the game can be joined at, after it anyone can close
+    // the game back to the promoter, 0 for no expiry
+    pub open_until: i64,
+}
+
+impl Params {
+    /// Lamports the promoter locks in the game account with `config`,
+    /// lets a client show the amount before signing.
+    pub fn collateral(&self, config: &Config, rent: &Rent) -> Result<u64, ProgramError> {
+        let level = GameLevel::from_u8(&self.game_level)?;
+        let collateral = required_collateral(
+            config.bet(level),
+            self.odds_x100,
+            config.fee_bps,
+            rent.minimum_balance(GameRecord::LEN),
+        )?;
+
+        Ok(collateral)
+    }
+
+    /// The reveal time the game gets under `config`.
+    pub fn reveal_time_for(&self, config: &Config) -> Result<i64, ProgramError> {
+        let reveal_time = match self.reveal_time {
+            0 => config.reveal_time,
+            reveal_time => reveal_time,
+        };
+
+        if !config.allows_reveal_time(reveal_time) {
+            msg!("reveal time {} is out of the config bounds", reveal_time);
+            return Err(GameError::InvalidRevealTime.into());
+        }
+
+        Ok(reveal_time)
+    }
 }
 
 
@@ -35,6 +76,8 @@ pub struct Accounts<'a, T> {
     pub game_recorder: &'a T,
     /// The system program account
     pub system_program: &'a T,
+    /// The program config
+    pub config: &'a T,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -46,13 +89,14 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             promoter:next_account_info(accounts_iter)?,
             game_recorder: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
         })
     }
 
     pub fn check(&self) -> ProgramResult {
-        check_account_key(self.system_program, &SYSTEM_ID).unwrap();
+        check_account_key(self.system_program, &SYSTEM_ID)?;
 
-        check_signer(self.promoter).unwrap();
+        check_signer(self.promoter)?;
         msg!("promoter ok");
 
         Ok(())
@@ -66,14 +110,31 @@ pub fn process_create_game<'a, 'b: 'a>(
 ) -> ProgramResult {
 
     let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
 
     let game_record = accounts.game_recorder;
-    let (game, game_seeds) = 
-        get_seeds_and_key(params.splicing_hash.to_vec());
-    check_account_key(game_record, &game)?;
+    // the canonical bump, so a commitment backs a single game address
+    let (game, bump) = find_game_key(&params.splicing_hash);
+    if game_record.key != &game {
+        msg!("game account doesn't match the splicing hash");
+        return Err(GameError::InvalidGameAccount.into());
+    }
+
+    let config = load_config(accounts.config)?;
+    let level = GameLevel::from_u8(&params.game_level)?;
+    if !config.is_level_enabled(level) {
+        msg!("level {:?} is disabled", level);
+        return Err(GameError::LevelDisabled.into());
+    }
 
-    let pre_store: u64 = GameLevel::from_u8(&params.game_level)?.get_bet() *
-        std::cmp::max(params.odds_x100, (params.max_number * 100) as u32) as u64;
+    let bet = config.bet(level);
+    let reveal_time = params.reveal_time_for(&config)?;
+    if params.open_until != 0 && params.open_until < Clock::get()?.unix_timestamp {
+        msg!("open until {} is already over", params.open_until);
+        return Err(GameError::GameExpired.into());
+    }
+    let pre_store = params.collateral(&config, &Rent::get()?)?;
+    msg!("lock {} lamports of collateral", pre_store);
 
     invoke_signed(
         &system_instruction::create_account(
@@ -88,19 +149,37 @@ pub fn process_create_game<'a, 'b: 'a>(
             accounts.game_recorder.clone(),
             accounts.system_program.clone(),
         ],
-        &[&game_seeds.chunks(32).collect::<Vec<&[u8]>>()],
+        &[&get_game_seeds(&params.splicing_hash, &[bump])],
     )?;
     msg!("create game record account ok");
 
-    let record_init = GameRecord::new(
+    let mut record_init = GameRecord::new(
         *accounts.promoter.key, 
         params.max_number, 
         params.odds_x100,
-        params.game_level,
+        level,
+        bet,
     );
+    record_init.reveal_time = reveal_time;
+    record_init.open_until = params.open_until;
+    record_init.bump = bump;
+    record_init.fee_bps = config.fee_bps;
     let mut data = accounts.game_recorder.try_borrow_mut_data()?;
-    record_init.pack_into_slice(&mut data);
+    record_init.try_pack_into_slice(&mut data)?;
     msg!("init game record ok");
+
+    GameEvent::GameCreated(GameCreated {
+        game: *game_record.key,
+        promoter: record_init.promoter,
+        max: record_init.max,
+        odds_x100: record_init.odds_x100,
+        level: level as u8,
+        bet,
+        collateral: pre_store,
+        reveal_time,
+        open_until: record_init.open_until,
+    })
+    .emit();
     
     Ok(())
 }
\ No newline at end of file
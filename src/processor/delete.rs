@@ -3,11 +3,11 @@ use web3_utils::{
 };
 use solana_program::{
     account_info::{AccountInfo, next_account_info}, 
-    entrypoint::ProgramResult, msg,
+    entrypoint::ProgramResult,
     program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, 
 };
 
-use crate::{constants::SYSTEM_ID, state::game::GameRecord, };
+use crate::{constants::SYSTEM_ID, error::GameError, events::{GameDeleted, GameEvent}, msg, state::game::{GameRecord, GameStatus}, utils::{check_game_recorder, close_game_account}};
 
 #[derive(InstructionsAccount)]
 /// The required accounts for the `create` instruction
@@ -33,11 +33,13 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
     }
 
     pub fn check(&self) -> ProgramResult {
-        check_account_key(self.system_program, &SYSTEM_ID).unwrap();
+        check_account_key(self.system_program, &SYSTEM_ID)?;
 
-        check_signer(self.promoter).unwrap();
+        check_signer(self.promoter)?;
         msg!("promoter ok");
 
+        check_game_recorder(self.game_recorder)?;
+
         Ok(())
     }
 }
@@ -48,9 +50,10 @@ pub fn process_delete_game<'a, 'b: 'a>(
 ) -> ProgramResult {
 
     let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
 
     let game_record = accounts.game_recorder;
-    let data = {
+    let mut data = {
         let data_ref = game_record.try_borrow_data()?;    
         GameRecord::unpack_from_slice(&data_ref)?        
     }; 
@@ -59,25 +62,22 @@ pub fn process_delete_game<'a, 'b: 'a>(
 
     if &data.promoter != promoter.key {
         msg!("not your game");
-        return Err(ProgramError::InvalidArgument);
+        return Err(GameError::NotPromoter.into());
     }
 
-    if data.firing_point != 0 || data.player == crate::ID {
-        msg!("the game has started");
-        return Err(ProgramError::InvalidArgument);
-    }
+    data.transition(GameStatus::Cancelled)?;
 
-    let all_lamports = accounts.game_recorder.lamports();
+    let refund = game_record.lamports();
+    close_game_account(game_record, promoter)?;
+    msg!("return the pre store ok");
 
-    {
-        **accounts.game_recorder.try_borrow_mut_lamports()? -= all_lamports;
-        **accounts.promoter.try_borrow_mut_lamports()? += all_lamports;
-        
-        msg!("return the pre store ok");
-    }
+    GameEvent::GameDeleted(GameDeleted {
+        game: *game_record.key,
+        promoter: *promoter.key,
+        refund,
+        expired: false,
+    })
+    .emit();
 
-    let mut game_recorder_data = accounts.game_recorder.try_borrow_mut_data()?;
-    game_recorder_data.fill(0);
-    
     Ok(())
 }
\ No newline at end of file
@@ -3,16 +3,21 @@ use web3_utils::{
 };
 use solana_program::{
     account_info::{AccountInfo, next_account_info}, 
-    entrypoint::ProgramResult, msg,
-    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, 
+    entrypoint::ProgramResult,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+    clock::Clock,
 };
 
 
-use crate::{constants::{VAULT}, state::game::GameRecord, utils::{if_reveal_time, percent_of}};
+use crate::{
+    error::GameError, events::{GameEvent, GameSettled}, msg, settlement::{crank_reward, settle, Outcome}, state::game::{GameRecord, GameStatus},
+    utils::{check_game_recorder, close_game_account, if_reveal_time, load_config, load_vault},
+};
 
 #[derive(InstructionsAccount)]
-/// The required accounts for the `create` instruction
+/// The required accounts for the `end` instruction
 pub struct Accounts<'a, T> { 
+    /// The player, the promoter, or anyone once the game is open to settle
     #[cons(writable, signer)]
     pub terminator: &'a T,
     #[cons(writable)]
@@ -23,6 +28,8 @@ pub struct Accounts<'a, T> {
     pub game_recorder: &'a T,
     #[cons(writable)]
     pub vault: &'a T,
+    /// The program config
+    pub config: &'a T,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -36,14 +43,15 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             player: next_account_info(accounts_iter)?,
             game_recorder: next_account_info(accounts_iter)?,
             vault: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
         })
     }
 
     pub fn check(&self) -> ProgramResult {
-        check_account_key(self.vault, &VAULT).unwrap();
+        check_signer(self.terminator)?;
+        msg!("terminator ok");
 
-        check_signer(self.terminator).unwrap();
-        msg!("promoter ok");
+        check_game_recorder(self.game_recorder)?;
 
         Ok(())
     }
@@ -55,88 +63,105 @@ pub fn process_end_game<'a, 'b: 'a>(
 ) -> ProgramResult {
 
     let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
 
     let game_record = accounts.game_recorder;
     let player = accounts.player;
     let promoter = accounts.promoter;
 
-    let data = {
+    let config = load_config(accounts.config)?;
+    check_account_key(accounts.vault, &config.vault)?;
+    let mut vault = load_vault(accounts.vault)?;
+
+    let mut data = {
         let data_ref = game_record.try_borrow_data()?;    
         GameRecord::unpack_from_slice(&data_ref)?        
     }; 
 
-    if data.firing_point == 0 || data.player == crate::ID {
-        msg!("the game hasn't started");
-        return Err(ProgramError::InvalidArgument);
-    } 
+    let forfeited = data.status == GameStatus::Forfeited;
+    let revealed = data.status == GameStatus::Revealed;
+    data.transition(GameStatus::Settled)?;
 
-    let mut if_pay_more = match (if_reveal_time(data.shot_time)?, data.answer) {
-        (false, 0) => {
-            msg!("over time and no reveal");
-            true
+    let deadline = data.deadline(config.reveal_time)?;
+    let outcome = match (if_reveal_time(deadline)?, revealed) {
+        _ if forfeited => {
+            msg!("promoter revealed an answer out of range");
+            Outcome::Forfeit
         }
-        (false, _) => {
-            msg!("over time and revealed");
-            false
+        (false, false) => {
+            msg!("over time and no reveal");
+            Outcome::NoReveal
         }
-        (true, 0) => {
+        (true, false) => {
             msg!("Settlement cannot be made before reveal");
-            return Err(ProgramError::InvalidArgument);
+            return Err(GameError::RevealPending.into());
+        }
+        (_, true) if data.firing_point == data.answer => {
+            msg!("player win");
+            Outcome::PlayerWin
+        }
+        (_, true) => {
+            msg!("promoter win");
+            Outcome::PromoterWin
         }
-        (true, _) => {
-            msg!("not over time but revealed");
-            false
-        },
     };
 
-    if data.answer == 0 {
-        msg!("can't set 0 as the anwser");
-        if_pay_more = true
+    if &data.promoter != promoter.key {
+        msg!("give fault promoter");
+        return Err(GameError::PromoterMismatch.into());
     }
 
-    if &data.promoter != promoter.key || &data.player != player.key {
-        msg!("give fault promoter or player");
-        return Err(ProgramError::InvalidArgument);
+    if &data.player != player.key {
+        msg!("give fault player");
+        return Err(GameError::PlayerMismatch.into());
     }
 
     let terminator = accounts.terminator;
+    let crank = terminator.key != &data.player && terminator.key != &data.promoter;
 
-    if terminator.key != &data.player && terminator.key != &data.promoter {
-        msg!("Incorrect Settler");
-        return Err(ProgramError::InvalidArgument);
+    if crank {
+        let now = Clock::get()?.unix_timestamp;
+        if !data.is_open_to_settle(now, deadline, config.settle_grace_time)? {
+            msg!("Incorrect Settler, the game isn't open to settle yet");
+            return Err(GameError::InvalidSettler.into());
+        }
     }
 
-    let all_lamports = accounts.game_recorder.lamports();
-    let win: u64 = data.level.get_bet() * data.odds_x100 as u64 / 100;
-
-    if data.firing_point == data.answer {
-        msg!("player win");
-
-        let vault_fee = percent_of(win, 1);
-        let player_gain = win.checked_sub(vault_fee).unwrap();
-        let promoter_back = all_lamports.checked_sub(win).unwrap();
-        
-        **accounts.game_recorder.try_borrow_mut_lamports()? -= all_lamports;
-        **accounts.player.try_borrow_mut_lamports()? += player_gain;
-        **accounts.promoter.try_borrow_mut_lamports()? += promoter_back;
-        **accounts.vault.try_borrow_mut_lamports()? += vault_fee;
-    }else {
-        msg!("promoter win");
-
-        let vault_fee = percent_of(win, 1);
-
-        **accounts.game_recorder.try_borrow_mut_lamports()? -= all_lamports;
-        if if_pay_more {
-            msg!("promoter didn't reveal the anwser");
-            **accounts.player.try_borrow_mut_lamports()? += all_lamports.checked_sub(vault_fee).unwrap();
-        }else {
-            **accounts.promoter.try_borrow_mut_lamports()? += all_lamports.checked_sub(vault_fee).unwrap();
-        }
-        **accounts.vault.try_borrow_mut_lamports()? += vault_fee;
+    // the game PDA escrows the promoter's collateral plus the player's stake,
+    // the settlement hands out exactly that amount and closing the account
+    // gives the rest, the rent included, back to the promoter who paid it
+    let rent = data.rent_reserve(&Rent::get()?, game_record.data_len());
+    let escrow = game_record.lamports().checked_sub(rent).ok_or(GameError::InsufficientEscrow)?;
+    let settlement = settle(outcome, escrow, data.stake(), data.odds_x100, data.fee(config.fee_bps))?;
+
+    // a third party settler is paid out of the vault's fee
+    let reward = if crank { crank_reward(settlement.to_vault, config.crank_reward_bps)? } else { 0 };
+    let to_vault = settlement.to_vault.checked_sub(reward).ok_or(GameError::MathOverflow)?;
+    msg!("crank reward {} lamports", reward);
+
+    **game_record.try_borrow_mut_lamports()? -= settlement.to_player + settlement.to_vault;
+    **player.try_borrow_mut_lamports()? += settlement.to_player;
+    **accounts.vault.try_borrow_mut_lamports()? += to_vault;
+    **terminator.try_borrow_mut_lamports()? += reward;
+    close_game_account(game_record, promoter)?;
+
+    vault.fees_collected = vault.fees_collected.saturating_add(to_vault);
+    {
+        let mut vault_data = accounts.vault.try_borrow_mut_data()?;
+        vault.try_pack_into_slice(&mut vault_data)?;
     }
 
-    let mut game_recorder_data = accounts.game_recorder.try_borrow_mut_data()?;
-    game_recorder_data.fill(0);
-    
+    GameEvent::GameSettled(GameSettled {
+        game: *game_record.key,
+        settler: *terminator.key,
+        outcome,
+        to_player: settlement.to_player,
+        to_promoter: settlement.to_promoter,
+        to_vault,
+        crank_reward: reward,
+        rent,
+    })
+    .emit();
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use web3_utils::{
+    BorshSize, InstructionsAccount, accounts::InstructionsAccount, borsh_size::BorshSize, check::{check_account_key, check_signer}
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+};
+
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    constants::{CONFIG_SEED, SYSTEM_ID, VAULT_SEED}, error::GameError,
+    msg, state::{config::{Config, LEVEL_COUNT}, vault::Vault},
+    utils::{get_config_key, get_upgrade_authority, get_vault_key},
+};
+
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct Params {
+    // settlement fee in basis points of the win
+    pub fee_bps: u16,
+    // seconds the promoter has to reveal, for games that don't pick their own
+    pub reveal_time: i64,
+    // bit i set means GameLevel i can be created
+    pub enabled_levels: u8,
+    // bet in lamports of every GameLevel
+    pub bets: [u64; LEVEL_COUNT],
+    // bounds of the reveal time a game can be created with
+    pub min_reveal_time: i64,
+    pub max_reveal_time: i64,
+    // share of the vault fee paid to a third party settler, in basis points
+    pub crank_reward_bps: u16,
+    // seconds after the reveal only the player or promoter can settle
+    pub settle_grace_time: i64,
+}
+
+impl Params {
+    pub fn into_config(self, admin: Pubkey) -> Config {
+        Config {
+            admin,
+            vault: get_vault_key().0,
+            fee_bps: self.fee_bps,
+            reveal_time: self.reveal_time,
+            enabled_levels: self.enabled_levels,
+            bets: self.bets,
+            min_reveal_time: self.min_reveal_time,
+            max_reveal_time: self.max_reveal_time,
+            crank_reward_bps: self.crank_reward_bps,
+            settle_grace_time: self.settle_grace_time,
+        }
+    }
+}
+
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `init_config` instruction
+pub struct Accounts<'a, T> {
+    /// The program's upgrade authority, becomes the config admin
+    #[cons(writable, signer)]
+    pub admin: &'a T,
+    #[cons(writable)]
+    pub config: &'a T,
+    /// The fee vault, withdrawable by the admin until `SetWithdrawAuthority`
+    #[cons(writable)]
+    pub vault: &'a T,
+    /// The program's ProgramData account
+    pub program_data: &'a T,
+    /// The system program account
+    pub system_program: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            admin: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
+            vault: next_account_info(accounts_iter)?,
+            program_data: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        })
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        check_account_key(self.system_program, &SYSTEM_ID)?;
+
+        check_signer(self.admin)?;
+        msg!("admin ok");
+
+        Ok(())
+    }
+}
+
+pub fn process_init_config<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
+
+    if get_upgrade_authority(accounts.program_data)? != Some(*accounts.admin.key) {
+        msg!("only the upgrade authority can init the config");
+        return Err(GameError::Unauthorized.into());
+    }
+
+    let (config_key, bump) = get_config_key();
+    check_account_key(accounts.config, &config_key)?;
+
+    let config = params.into_config(*accounts.admin.key);
+    config.validate()?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            accounts.admin.key,
+            accounts.config.key,
+            Rent::get()?.minimum_balance(Config::LEN),
+            Config::LEN as u64,
+            &crate::ID
+        ),
+        &[
+            accounts.admin.clone(),
+            accounts.config.clone(),
+            accounts.system_program.clone(),
+        ],
+        &[&[CONFIG_SEED, &[bump]]],
+    )?;
+    msg!("create config account ok");
+
+    {
+        let mut data = accounts.config.try_borrow_mut_data()?;
+        config.try_pack_into_slice(&mut data)?;
+        msg!("init config ok");
+    }
+
+    let (vault_key, vault_bump) = get_vault_key();
+    check_account_key(accounts.vault, &vault_key)?;
+
+    invoke_signed(
+        &system_instruction::create_account(
+            accounts.admin.key,
+            accounts.vault.key,
+            Rent::get()?.minimum_balance(Vault::LEN),
+            Vault::LEN as u64,
+            &crate::ID
+        ),
+        &[
+            accounts.admin.clone(),
+            accounts.vault.clone(),
+            accounts.system_program.clone(),
+        ],
+        &[&[VAULT_SEED, &[vault_bump]]],
+    )?;
+    msg!("create vault account ok");
+
+    let vault = Vault::new(&[*accounts.admin.key], 1)?;
+    let mut data = accounts.vault.try_borrow_mut_data()?;
+    vault.try_pack_into_slice(&mut data)?;
+    msg!("init vault ok");
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use web3_utils::{
+    InstructionsAccount, accounts::InstructionsAccount, check::{check_account_key, check_account_owner, check_signer}
+};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult, program::invoke,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+};
+
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{constants::SYSTEM_ID, msg, state::game::GameRecord, utils::check_game_not_closed};
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `migrate` instruction
+pub struct Accounts<'a, T> {
+    /// Pays the rent for the bigger layout
+    #[cons(writable, signer)]
+    pub payer: &'a T,
+    #[cons(writable)]
+    pub game_recorder: &'a T,
+    /// The system program account
+    pub system_program: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            payer:next_account_info(accounts_iter)?,
+            game_recorder: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+        })
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        check_account_key(self.system_program, &SYSTEM_ID)?;
+
+        check_signer(self.payer)?;
+        msg!("payer ok");
+
+        check_game_not_closed(self.game_recorder)?;
+        check_account_owner(self.game_recorder, &crate::ID)?;
+
+        Ok(())
+    }
+}
+
+/// Rewrites a v1 game record into the current layout, keeping its lamports.
+///
+/// The whole v1 balance stays escrow, only the rent top up is held back and,
+/// like the rest of the rent, goes to the promoter when the game is closed:
+/// a payer other than the promoter doesn't get it back.
+pub fn process_migrate_game<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
+
+    let game_record = accounts.game_recorder;
+    let data = {
+        let data_ref = game_record.try_borrow_data()?;
+        GameRecord::unpack_v1(&data_ref)?
+    };
+    msg!("read v1 game record ok, status: {:?}", data.status);
+
+    let rent = Rent::get()?;
+    let rent_top_up = rent.minimum_balance(GameRecord::LEN)
+        .saturating_sub(rent.minimum_balance(GameRecord::V1_LEN));
+
+    if rent_top_up > 0 {
+        invoke(
+            &system_instruction::transfer(
+                accounts.payer.key,
+                game_record.key,
+                rent_top_up
+            ),
+            &[
+                accounts.payer.clone(),
+                accounts.game_recorder.clone(),
+                accounts.system_program.clone(),
+            ]
+        )?;
+        msg!("pay extra rent ok");
+    }
+
+    game_record.resize(GameRecord::LEN)?;
+
+    {
+        let mut data_mut = game_record.try_borrow_mut_data()?;
+        data.try_pack_into_slice(&mut data_mut)?;
+        msg!("write v2 game record ok");
+    }
+
+    Ok(())
+}
@@ -3,15 +3,19 @@ use web3_utils::{
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    account_info::{AccountInfo, next_account_info}, clock::Clock, entrypoint::ProgramResult, msg, program::{invoke}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, sysvar::Sysvar
+    account_info::{AccountInfo, next_account_info}, clock::Clock, entrypoint::ProgramResult, program::{invoke}, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar
 };
 
 use solana_system_interface::instruction as system_instruction;
 
-use crate::{constants::SYSTEM_ID, state::game::GameRecord};
+use crate::{
+    constants::SYSTEM_ID, error::GameError, events::{GameEvent, GameJoined}, msg, settlement::check_solvency,
+    state::game::{GameRecord, GameStatus},
+    utils::{check_game_recorder, load_config},
+};
 
 
-#[derive(BorshDeserialize, BorshSerialize, BorshSize, Debug)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
 pub struct Params {
     pub point: u16,
 }
@@ -26,9 +30,10 @@ pub struct Accounts<'a, T> {
     pub game_recorder: &'a T,
     /// The system program account
     pub system_program: &'a T,
-    #[cons(writable)]
     /// The promoter
     pub promoter: &'a T,
+    /// The program config
+    pub config: &'a T,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -41,13 +46,16 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             game_recorder: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
             promoter: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
         })
     }
 
     pub fn check(&self) -> ProgramResult {
-        check_account_key(self.system_program, &SYSTEM_ID).unwrap();
-        check_signer(self.player).unwrap();
-        msg!("promoter ok");
+        check_account_key(self.system_program, &SYSTEM_ID)?;
+        check_signer(self.player)?;
+        msg!("player ok");
+
+        check_game_recorder(self.game_recorder)?;
 
         Ok(())
     }
@@ -60,6 +68,7 @@ pub fn process_participate_game<'a, 'b: 'a>(
 ) -> ProgramResult {
 
     let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
 
     let promoter = accounts.promoter;
     let game_record = accounts.game_recorder;
@@ -71,37 +80,70 @@ pub fn process_participate_game<'a, 'b: 'a>(
 
     if params.point == 0 || params.point > data.max {
         msg!("x too large or x = 0");
-        return Err(ProgramError::InvalidArgument);
+        return Err(GameError::InvalidPoint.into());
     }
 
     if &data.promoter != promoter.key {
         msg!("give an fault promoter");
-        return Err(ProgramError::InvalidArgument);
+        return Err(GameError::PromoterMismatch.into());
+    }
+
+    if accounts.player.key == promoter.key {
+        msg!("promoter can't join own game");
+        return Err(GameError::PromoterCannotPlay.into());
     }
 
+    let now = Clock::get()?.unix_timestamp;
+    if data.is_expired(now) {
+        msg!("game was open until {}", data.open_until);
+        return Err(GameError::GameExpired.into());
+    }
+
+    data.transition(GameStatus::Joined)?;
+
+    // the stake stays in the game PDA until the game is settled
     invoke(
         &system_instruction::transfer(
             accounts.player.key, 
-            promoter.key, 
-            data.level.get_bet()
+            game_record.key, 
+            data.stake()
         ), 
         &[
             accounts.player.clone(),
-            accounts.promoter.clone(),
+            accounts.game_recorder.clone(),
             accounts.system_program.clone(),
         ]
     )?;
-    msg!("transfer bet ok");
+    msg!("escrow bet ok");
+
+    // the game must be able to pay whatever the outcome before anyone plays it
+    let config = load_config(accounts.config)?;
+    let escrow = game_record.lamports()
+        .saturating_sub(data.rent_reserve(&Rent::get()?, game_record.data_len()));
+    if let Err(err) = check_solvency(escrow, data.stake(), data.odds_x100, data.fee(config.fee_bps)) {
+        msg!("game escrow {} can't cover the payout", escrow);
+        return Err(err.into());
+    }
 
-    data.player = *promoter.key;
+    data.player = *accounts.player.key;
     data.firing_point = params.point;
-    data.shot_time = Clock::get()?.unix_timestamp;
+    data.shot_time = now;
+    data.set_reveal_deadline(data.shot_time, config.reveal_time)?;
 
     {
         let mut data_mut = game_record.try_borrow_mut_data()?;
-        data.pack_into_slice(&mut data_mut);   
+        data.try_pack_into_slice(&mut data_mut)?;   
         msg!("write game record ok");         
     }
+
+    GameEvent::GameJoined(GameJoined {
+        game: *game_record.key,
+        player: data.player,
+        point: data.firing_point,
+        stake: data.stake(),
+        reveal_deadline: data.reveal_deadline,
+    })
+    .emit();
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use web3_utils::{
+    InstructionsAccount, accounts::InstructionsAccount,
+};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    clock::Clock, entrypoint::ProgramResult,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, sysvar::Sysvar,
+};
+
+use crate::{error::GameError, events::{GameDeleted, GameEvent}, msg, state::game::{GameRecord, GameStatus}, utils::{check_game_recorder, close_game_account}};
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `reclaim` instruction, anyone can send it
+pub struct Accounts<'a, T> {
+    /// Gets every lamport of the game back
+    #[cons(writable)]
+    pub promoter: &'a T,
+    #[cons(writable)]
+    pub game_recorder: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            promoter: next_account_info(accounts_iter)?,
+            game_recorder: next_account_info(accounts_iter)?,
+        })
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        check_game_recorder(self.game_recorder)?;
+
+        Ok(())
+    }
+}
+
+/// Closes a game nobody joined before its `open_until`, returning the
+/// collateral and the rent to the promoter.
+pub fn process_reclaim_game<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
+
+    let game_record = accounts.game_recorder;
+    let mut data = {
+        let data_ref = game_record.try_borrow_data()?;
+        GameRecord::unpack_from_slice(&data_ref)?
+    };
+
+    let promoter = accounts.promoter;
+
+    if &data.promoter != promoter.key {
+        msg!("give fault promoter");
+        return Err(GameError::PromoterMismatch.into());
+    }
+
+    data.transition(GameStatus::Cancelled)?;
+
+    if !data.is_expired(Clock::get()?.unix_timestamp) {
+        msg!("game is open until {}", data.open_until);
+        return Err(GameError::GameNotExpired.into());
+    }
+
+    let refund = game_record.lamports();
+    close_game_account(game_record, promoter)?;
+    msg!("return the pre store ok");
+
+    GameEvent::GameDeleted(GameDeleted {
+        game: *game_record.key,
+        promoter: *promoter.key,
+        refund,
+        expired: true,
+    })
+    .emit();
+
+    Ok(())
+}
@@ -4,17 +4,34 @@ use web3_utils::{
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info}, 
-    entrypoint::ProgramResult, msg,
+    entrypoint::ProgramResult,
     program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, 
+    clock::Clock, sysvar::Sysvar,
 };
 
-use crate::{constants::SYSTEM_ID, state::game::GameRecord, utils::{get_seeds_and_key, get_splicing_hash, if_reveal_time}};
+use crate::{constants::SYSTEM_ID, error::GameError, events::{GameEvent, GameRevealed}, msg, state::game::{CommitmentScheme, GameRecord, GameStatus}, utils::{check_game_key, check_game_recorder, get_commitment_hash, get_splicing_hash, if_reveal_time, load_config}};
 
 
-#[derive(BorshDeserialize, BorshSerialize, BorshSize, Debug)]
+/// The secret the answer was committed with, must match the game's `CommitmentScheme`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+pub enum Secret {
+    Legacy { random: [u8; 6] },
+    Salted { salt: [u8; 32] },
+}
+
+impl BorshSize for Secret {
+    fn borsh_len(&self) -> usize {
+        1 + match self {
+            Secret::Legacy { .. } => 6,
+            Secret::Salted { .. } => 32,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
 pub struct Params {
     pub x: u16,
-    pub random: [u8; 6],
+    pub secret: Secret,
 }
 
 
@@ -27,6 +44,8 @@ pub struct Accounts<'a, T> {
     pub game_recorder: &'a T,
     /// The system program account
     pub system_program: &'a T,
+    /// The program config
+    pub config: &'a T,
 }
 
 impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
@@ -38,15 +57,18 @@ impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
             promoter:next_account_info(accounts_iter)?,
             game_recorder: next_account_info(accounts_iter)?,
             system_program: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
         })
     }
 
     pub fn check(&self) -> ProgramResult {
-        check_account_key(self.system_program, &SYSTEM_ID).unwrap();
+        check_account_key(self.system_program, &SYSTEM_ID)?;
 
-        check_signer(self.promoter).unwrap();
+        check_signer(self.promoter)?;
         msg!("promoter ok");
 
+        check_game_recorder(self.game_recorder)?;
+
         Ok(())
     }
 }
@@ -58,43 +80,75 @@ pub fn process_reveal_game<'a, 'b: 'a>(
 ) -> ProgramResult {
 
     let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
 
     let game_record = accounts.game_recorder;
-    let (game, _) = 
-        get_seeds_and_key(get_splicing_hash(params.x, params.random));
-    check_account_key(game_record, &game)?;
-    msg!("gived x and random is correct");
-
     let mut data = {
         let data_ref = game_record.try_borrow_data()?;    
         GameRecord::unpack_from_slice(&data_ref)?        
     }; 
 
+    let commitment = match (data.commitment, &params.secret) {
+        (CommitmentScheme::Legacy, Secret::Legacy { random }) => {
+            data.random_string = *random;
+            get_splicing_hash(params.x, *random)
+        }
+        (CommitmentScheme::Salted, Secret::Salted { salt }) => {
+            get_commitment_hash(&data.promoter, data.max, params.x, salt)
+        }
+        _ => {
+            msg!("secret doesn't match the game's commitment scheme {:?}", data.commitment);
+            return Err(GameError::WrongCommitmentScheme.into());
+        }
+    };
+
+    if check_game_key(game_record.key, &commitment, data.bump).is_err() {
+        msg!("gived x and secret don't match the game");
+        return Err(GameError::InvalidGameAccount.into());
+    }
+    msg!("gived x and secret is correct");
+
     let promoter = accounts.promoter;
 
     if &data.promoter != promoter.key {
         msg!("not your game");
-        return Err(ProgramError::InvalidArgument);
+        return Err(GameError::NotPromoter.into());
     }
 
-    if data.firing_point == 0 || data.player == crate::ID {
-        msg!("the game hasn't started");
-        return Err(ProgramError::InvalidArgument);
-    } 
+    // the commitment is proven at this point, so an impossible answer can't
+    // be taken back: the promoter forfeits the escrow instead
+    if params.x == 0 || params.x > data.max {
+        msg!("committed answer {} is out of 1..={}", params.x, data.max);
+        data.transition(GameStatus::Forfeited)?;
+    } else {
+        data.transition(GameStatus::Revealed)?;
+    }
 
-    if !if_reveal_time(data.shot_time)? {
+    // only games joined before the deadline was stored need the config
+    let deadline = match data.reveal_deadline {
+        0 => data.deadline(load_config(accounts.config)?.reveal_time)?,
+        deadline => deadline,
+    };
+    if !if_reveal_time(deadline)? {
         msg!("over the reveal time");
-        return Err(ProgramError::InvalidArgument);
+        return Err(GameError::RevealTimeExpired.into());
     }
 
     data.answer = params.x;
-    data.random_string = params.random;
+    data.revealed_at = Clock::get()?.unix_timestamp;
 
     {
         let mut data_mut = game_record.try_borrow_mut_data()?;
-        data.pack_into_slice(&mut data_mut);   
+        data.try_pack_into_slice(&mut data_mut)?;   
         msg!("update game answer ok");         
     }
+
+    GameEvent::GameRevealed(GameRevealed {
+        game: *game_record.key,
+        answer: data.answer,
+        forfeited: data.status == GameStatus::Forfeited,
+    })
+    .emit();
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,71 @@
+use web3_utils::{
+    BorshSize, InstructionsAccount, accounts::InstructionsAccount, borsh_size::BorshSize,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{msg, state::vault::Vault, utils::load_vault};
+
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct Params {
+    // how many of `signers` must sign a withdrawal
+    pub threshold: u8,
+    // the withdraw authority members, at most `MAX_WITHDRAW_SIGNERS`
+    pub signers: Vec<Pubkey>,
+}
+
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `set_withdraw_authority` instruction
+pub struct Accounts<'a, T> {
+    #[cons(writable)]
+    pub vault: &'a T,
+    /// At least `threshold` members of the current withdraw authority, the
+    /// admin alone right after `InitConfig`
+    #[cons(signer)]
+    pub signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            vault: next_account_info(accounts_iter)?,
+            signers: accounts_iter.as_slice(),
+        })
+    }
+
+    pub fn check(&self, vault: &Vault) -> ProgramResult {
+        vault.check_approvals(self.signers.iter().filter(|a| a.is_signer).map(|a| a.key))?;
+        msg!("withdraw authority ok");
+
+        Ok(())
+    }
+}
+
+pub fn process_set_withdraw_authority<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+
+    let mut vault = load_vault(accounts.vault)?;
+    accounts.check(&vault)?;
+
+    vault.set_authority(&params.signers, params.threshold)?;
+
+    let mut data = accounts.vault.try_borrow_mut_data()?;
+    vault.try_pack_into_slice(&mut data)?;
+    msg!("set withdraw authority ok: {} of {}", params.threshold, params.signers.len());
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use web3_utils::{
+    BorshSize, InstructionsAccount, accounts::InstructionsAccount, borsh_size::BorshSize, check::check_signer
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{error::GameError, msg, utils::load_config};
+
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct Params {
+    pub new_admin: Pubkey,
+}
+
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `transfer_admin` instruction
+pub struct Accounts<'a, T> {
+    #[cons(signer)]
+    pub admin: &'a T,
+    #[cons(writable)]
+    pub config: &'a T,
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            admin: next_account_info(accounts_iter)?,
+            config: next_account_info(accounts_iter)?,
+        })
+    }
+
+    pub fn check(&self) -> ProgramResult {
+        check_signer(self.admin)?;
+        msg!("admin ok");
+
+        Ok(())
+    }
+}
+
+pub fn process_transfer_admin<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+    accounts.check()?;
+
+    let mut config = load_config(accounts.config)?;
+    if &config.admin != accounts.admin.key {
+        msg!("not the config admin");
+        return Err(GameError::Unauthorized.into());
+    }
+
+    config.admin = params.new_admin;
+
+    let mut data = accounts.config.try_borrow_mut_data()?;
+    config.try_pack_into_slice(&mut data)?;
+    msg!("transfer admin ok");
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use web3_utils::{
+    BorshSize, InstructionsAccount, accounts::InstructionsAccount, borsh_size::BorshSize,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar::Sysvar,
+};
+
+use crate::{error::GameError, msg, state::vault::Vault, utils::load_vault};
+
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSize, Clone, Debug, PartialEq)]
+pub struct Params {
+    pub amount: u64,
+}
+
+
+#[derive(InstructionsAccount)]
+/// The required accounts for the `withdraw_fees` instruction
+pub struct Accounts<'a, T> {
+    #[cons(writable)]
+    pub vault: &'a T,
+    /// Receives the withdrawn lamports
+    #[cons(writable)]
+    pub destination: &'a T,
+    /// At least `threshold` members of the vault's withdraw authority
+    #[cons(signer)]
+    pub signers: &'a [T],
+}
+
+impl<'a, 'b: 'a> Accounts<'a, AccountInfo<'b>> {
+    pub fn parse(
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Accounts {
+            vault: next_account_info(accounts_iter)?,
+            destination: next_account_info(accounts_iter)?,
+            signers: accounts_iter.as_slice(),
+        })
+    }
+
+    pub fn check(&self, vault: &Vault) -> ProgramResult {
+        vault.check_approvals(self.signers.iter().filter(|a| a.is_signer).map(|a| a.key))?;
+        msg!("withdraw authority ok");
+
+        Ok(())
+    }
+}
+
+pub fn process_withdraw_fees<'a, 'b: 'a>(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: Params,
+) -> ProgramResult {
+
+    let accounts = Accounts::parse(accounts)?;
+
+    let mut vault = load_vault(accounts.vault)?;
+    accounts.check(&vault)?;
+
+    if accounts.destination.key == accounts.vault.key {
+        msg!("can't withdraw into the vault");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // the rent exemption stays so the vault keeps its accounting
+    let available = accounts.vault.lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(Vault::LEN));
+    if params.amount > available {
+        msg!("withdraw {} but only {} available", params.amount, available);
+        return Err(GameError::InsufficientVaultBalance.into());
+    }
+
+    **accounts.vault.try_borrow_mut_lamports()? -= params.amount;
+    **accounts.destination.try_borrow_mut_lamports()? += params.amount;
+
+    vault.fees_withdrawn = vault.fees_withdrawn.saturating_add(params.amount);
+
+    let mut data = accounts.vault.try_borrow_mut_data()?;
+    vault.try_pack_into_slice(&mut data)?;
+    msg!("withdraw fees ok");
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Payout math shared by the processors and off-chain clients.
+//!
+//! Everything here is pure and overflow checked: a result that doesn't fit
+//! in a `u64` is a `GameError::MathOverflow`, never a panic or a wrap.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::error::GameError;
+
+/// How a game ended, decides who gets the escrow.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// the player guessed the revealed answer
+    PlayerWin,
+    /// the revealed answer differs from the guess
+    PromoterWin,
+    /// the reveal time is over and the promoter never revealed
+    NoReveal,
+    /// the promoter revealed an answer out of `1..=max`
+    Forfeit,
+}
+
+/// Where the escrow of a settled game goes, always sums up to the escrow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    pub to_player: u64,
+    pub to_promoter: u64,
+    pub to_vault: u64,
+}
+
+/// What the player gets back on a win, stake included.
+pub fn win_amount(bet: u64, odds_x100: u32) -> Result<u64, GameError> {
+    let win = bet as u128 * odds_x100 as u128 / 100;
+    u64::try_from(win).map_err(|_| GameError::MathOverflow)
+}
+
+/// Lamports the promoter has to lock when creating a game: the win, the
+/// vault's cut of it and the `rent` exemption of the game account.
+///
+/// The rent goes back to the promoter on settlement, the rest is the escrow
+/// `settle` splits once the player's stake is added.
+pub fn required_collateral(bet: u64, odds_x100: u32, fee_bps: u16, rent: u64) -> Result<u64, GameError> {
+    let win = win_amount(bet, odds_x100)?;
+    let fee = vault_fee(Outcome::PlayerWin, win, fee_bps)?;
+
+    win.checked_add(fee)
+        .and_then(|v| v.checked_add(rent))
+        .ok_or(GameError::MathOverflow)
+}
+
+/// Checks an `escrow` (rent excluded, stake included) can pay any outcome.
+pub fn check_solvency(escrow: u64, bet: u64, odds_x100: u32, fee_bps: u16) -> Result<(), GameError> {
+    let required = required_collateral(bet, odds_x100, fee_bps, 0)?
+        .checked_add(bet)
+        .ok_or(GameError::MathOverflow)?;
+    if escrow < required {
+        return Err(GameError::InsufficientEscrow);
+    }
+
+    Ok(())
+}
+
+/// The vault's cut, in basis points of the win.
+pub fn vault_fee(outcome: Outcome, win: u64, fee_bps: u16) -> Result<u64, GameError> {
+    match outcome {
+        Outcome::Forfeit => Ok(0),
+        _ => {
+            let fee = win as u128 * fee_bps as u128 / 10_000;
+            u64::try_from(fee).map_err(|_| GameError::MathOverflow)
+        }
+    }
+}
+
+/// The settler's cut of the vault fee `to_vault`, paid when someone other
+/// than the player or the promoter settles a game.
+pub fn crank_reward(to_vault: u64, crank_reward_bps: u16) -> Result<u64, GameError> {
+    let reward = to_vault as u128 * crank_reward_bps as u128 / 10_000;
+    u64::try_from(reward).map_err(|_| GameError::MathOverflow)
+}
+
+/// What the player receives out of `escrow`.
+pub fn player_payout(outcome: Outcome, escrow: u64, win: u64, fee: u64) -> Result<u64, GameError> {
+    match outcome {
+        Outcome::PlayerWin => win.checked_sub(fee).ok_or(GameError::MathOverflow),
+        Outcome::NoReveal => escrow.checked_sub(fee).ok_or(GameError::InsufficientEscrow),
+        Outcome::Forfeit => Ok(escrow),
+        Outcome::PromoterWin => Ok(0),
+    }
+}
+
+/// What the promoter gets back out of `escrow`.
+pub fn promoter_refund(outcome: Outcome, escrow: u64, win: u64, fee: u64) -> Result<u64, GameError> {
+    match outcome {
+        Outcome::PlayerWin => escrow.checked_sub(win).ok_or(GameError::InsufficientEscrow),
+        Outcome::PromoterWin => escrow.checked_sub(fee).ok_or(GameError::InsufficientEscrow),
+        Outcome::NoReveal | Outcome::Forfeit => Ok(0),
+    }
+}
+
+/// Splits the `escrow` of a game with the given terms, the rent exemption of
+/// the game account is not part of it.
+pub fn settle(
+    outcome: Outcome,
+    escrow: u64,
+    bet: u64,
+    odds_x100: u32,
+    fee_bps: u16,
+) -> Result<Settlement, GameError> {
+    let win = win_amount(bet, odds_x100)?;
+    let to_vault = vault_fee(outcome, win, fee_bps)?;
+
+    let settlement = Settlement {
+        to_player: player_payout(outcome, escrow, win, to_vault)?,
+        to_promoter: promoter_refund(outcome, escrow, win, to_vault)?,
+        to_vault,
+    };
+
+    let total = settlement.to_player
+        .checked_add(settlement.to_promoter)
+        .and_then(|v| v.checked_add(settlement.to_vault))
+        .ok_or(GameError::MathOverflow)?;
+    if total != escrow {
+        return Err(GameError::InsufficientEscrow);
+    }
+
+    Ok(settlement)
+}
@@ -0,0 +1,221 @@
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::GameError,
+    msg,
+    state::{game::GameLevel, header::{AccountHeader, AccountTag}, read},
+};
+
+/// Number of `GameLevel`s
+pub const LEVEL_COUNT: usize = 5;
+
+/// Program wide settings, stored in the PDA at `utils::get_config_key`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// who can update the config
+    pub admin: Pubkey,
+    /// the fee vault PDA, see `utils::get_vault_key`
+    pub vault: Pubkey,
+    /// settlement fee in basis points of the win
+    pub fee_bps: u16,
+    /// seconds the promoter has to reveal after the player joined, for
+    /// games created without their own reveal time
+    pub reveal_time: i64,
+    /// bit `i` set means `GameLevel` `i` can be created
+    pub enabled_levels: u8,
+    /// bet in lamports of every `GameLevel`, indexed by level
+    pub bets: [u64; LEVEL_COUNT],
+    /// shortest reveal time a game can be created with
+    pub min_reveal_time: i64,
+    /// longest reveal time a game can be created with
+    pub max_reveal_time: i64,
+    /// share of the vault fee, in basis points, paid to a settler who is
+    /// neither the player nor the promoter
+    pub crank_reward_bps: u16,
+    /// seconds after the reveal a game stays reserved to its player and
+    /// promoter to settle
+    pub settle_grace_time: i64,
+}
+
+impl Sealed for Config {}
+
+impl Config {
+    /// current layout version
+    pub const VERSION: u8 = 1;
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    const RESERVED_LEN: usize = 38;
+
+    pub fn bet(&self, level: GameLevel) -> u64 {
+        self.bets[level as usize]
+    }
+
+    pub fn is_level_enabled(&self, level: GameLevel) -> bool {
+        self.enabled_levels & (1 << level as u8) != 0
+    }
+
+    /// Whether a game can be created with `reveal_time`.
+    pub fn allows_reveal_time(&self, reveal_time: i64) -> bool {
+        (self.min_reveal_time..=self.max_reveal_time).contains(&reveal_time)
+    }
+
+    /// Rejects settings the processors can't work with.
+    pub fn validate(&self) -> Result<(), ProgramError> {
+        if self.fee_bps > Self::MAX_FEE_BPS {
+            msg!("fee {} bps is over {}", self.fee_bps, Self::MAX_FEE_BPS);
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        if self.crank_reward_bps > Self::MAX_FEE_BPS {
+            msg!("crank reward {} bps is over {}", self.crank_reward_bps, Self::MAX_FEE_BPS);
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        if self.settle_grace_time < 0 {
+            msg!("settle grace time can't be negative");
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        if self.min_reveal_time <= 0 {
+            msg!("reveal time must be positive");
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        if !(self.min_reveal_time..=self.max_reveal_time).contains(&self.reveal_time) {
+            msg!(
+                "reveal time {} is out of {}..={}",
+                self.reveal_time, self.min_reveal_time, self.max_reveal_time
+            );
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        if self.bets.contains(&0) {
+            msg!("every level needs a bet");
+            return Err(GameError::InvalidConfig.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// total size = 2 + 32 + 32 + 2 + 8 + 1 + 40 + 8 + 8 + 2 + 8 + 38 = 181 bytes
+impl Pack for Config {
+    const LEN: usize =
+        AccountHeader::LEN + 32 + 32 + 2 + 8 + 1 + 8 * LEVEL_COUNT + 8 + 8 + 2 + 8 + Self::RESERVED_LEN;
+
+    /// Panics on a too small `dst`, processors use `try_pack_into_slice`.
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        self.try_pack_into_slice(dst).expect("destination slice too small for Config");
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Self::LEN {
+            msg!("Source slice too small for Config");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        AccountHeader::unpack_from_slice(src)?.check(AccountTag::Config, Self::VERSION)?;
+
+        let mut offset = AccountHeader::LEN;
+
+        let admin = Pubkey::new_from_array(read(src, offset)?);
+        offset += 32;
+
+        let vault = Pubkey::new_from_array(read(src, offset)?);
+        offset += 32;
+
+        let fee_bps = u16::from_le_bytes(read(src, offset)?);
+        offset += 2;
+
+        let reveal_time = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        let enabled_levels = src[offset];
+        offset += 1;
+
+        let mut bets = [0u64; LEVEL_COUNT];
+        for bet in bets.iter_mut() {
+            *bet = u64::from_le_bytes(read(src, offset)?);
+            offset += 8;
+        }
+
+        let min_reveal_time = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        let max_reveal_time = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        let crank_reward_bps = u16::from_le_bytes(read(src, offset)?);
+        offset += 2;
+
+        let settle_grace_time = i64::from_le_bytes(read(src, offset)?);
+
+        Ok(Self {
+            admin,
+            vault,
+            fee_bps,
+            reveal_time,
+            enabled_levels,
+            bets,
+            min_reveal_time,
+            max_reveal_time,
+            crank_reward_bps,
+            settle_grace_time,
+        })
+    }
+}
+
+impl Config {
+    /// Writes the config into `dst`, which is left untouched if it's too small.
+    pub fn try_pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < Self::LEN {
+            msg!("Destination slice too small for Config: {} < {}", dst.len(), Self::LEN);
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        AccountHeader::new(AccountTag::Config, Self::VERSION)
+            .pack_into_slice(&mut dst[..AccountHeader::LEN]);
+
+        let mut offset = AccountHeader::LEN;
+
+        dst[offset..offset + 32].copy_from_slice(self.admin.as_ref());
+        offset += 32;
+
+        dst[offset..offset + 32].copy_from_slice(self.vault.as_ref());
+        offset += 32;
+
+        dst[offset..offset + 2].copy_from_slice(&self.fee_bps.to_le_bytes());
+        offset += 2;
+
+        dst[offset..offset + 8].copy_from_slice(&self.reveal_time.to_le_bytes());
+        offset += 8;
+
+        dst[offset] = self.enabled_levels;
+        offset += 1;
+
+        for bet in self.bets {
+            dst[offset..offset + 8].copy_from_slice(&bet.to_le_bytes());
+            offset += 8;
+        }
+
+        dst[offset..offset + 8].copy_from_slice(&self.min_reveal_time.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.max_reveal_time.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 2].copy_from_slice(&self.crank_reward_bps.to_le_bytes());
+        offset += 2;
+
+        dst[offset..offset + 8].copy_from_slice(&self.settle_grace_time.to_le_bytes());
+        offset += 8;
+
+        dst[offset..Self::LEN].fill(0);
+
+        Ok(())
+    }
+}
@@ -3,9 +3,11 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::{Pack, Sealed},
     pubkey::Pubkey,
-    msg,
+    rent::Rent,
 };
 
+use crate::{error::GameError, msg, state::{header::{AccountHeader, AccountTag}, read}};
+
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub enum GameLevel {
@@ -17,6 +19,7 @@ pub enum GameLevel {
 }
 
 impl GameLevel {
+    /// Bets of the games created before the `Config` account existed.
     pub fn get_bet(&self) -> u64 {
         match self {
             GameLevel::D => 1_000_000,
@@ -34,7 +37,89 @@ impl GameLevel {
             2 => Ok(GameLevel::B),
             3 => Ok(GameLevel::C),
             4 => Ok(GameLevel::D),
-            _ => Err(ProgramError::InvalidInstructionData),
+            _ => Err(GameError::InvalidGameLevel.into()),
+        }
+    }
+}
+
+/// Lifecycle of a game record.
+///
+/// `Open -> Joined -> Revealed -> Settled`, a joined game can be settled
+/// without a reveal once the reveal time is over, a reveal of an out of range
+/// answer goes through `Forfeited` instead of `Revealed`, and an open game can
+/// be cancelled by its promoter.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum GameStatus {
+    /// created by the promoter, waiting for a player
+    Open,
+    /// a player has taken the shot
+    Joined,
+    /// the promoter has revealed the answer
+    Revealed,
+    /// the game has been paid out
+    Settled,
+    /// the promoter deleted the game before anyone joined
+    Cancelled,
+    /// the revealed answer was outside `1..=max`, the player takes the whole escrow
+    Forfeited,
+}
+
+impl GameStatus {
+    pub fn from_u8(value: &u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(GameStatus::Open),
+            1 => Ok(GameStatus::Joined),
+            2 => Ok(GameStatus::Revealed),
+            3 => Ok(GameStatus::Settled),
+            4 => Ok(GameStatus::Cancelled),
+            5 => Ok(GameStatus::Forfeited),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// The single source of truth for which lifecycle moves are legal.
+    pub fn check_transition(&self, next: GameStatus) -> Result<(), GameError> {
+        use GameStatus::*;
+
+        match (self, next) {
+            (Open, Joined)
+            | (Open, Cancelled)
+            | (Joined, Revealed)
+            | (Joined, Forfeited)
+            | (Joined, Settled)
+            | (Revealed, Settled)
+            | (Forfeited, Settled) => Ok(()),
+            (Settled, _) | (Cancelled, _) => Err(GameError::GameFinished),
+            (Open, _) => Err(GameError::GameNotStarted),
+            (_, Joined) | (_, Cancelled) => Err(GameError::GameAlreadyStarted),
+            (Revealed, Revealed)
+            | (Revealed, Forfeited)
+            | (Forfeited, Revealed)
+            | (Forfeited, Forfeited) => Err(GameError::AlreadyRevealed),
+            _ => Err(GameError::InvalidStatusTransition),
+        }
+    }
+}
+
+/// Preimage format of the hash a game's PDA is derived from.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum CommitmentScheme {
+    /// `utils::get_splicing_hash`: the answer and a 6 bytes random string,
+    /// only kept so games created before the salted scheme can be revealed
+    Legacy,
+    /// `utils::get_commitment_hash`: domain tag, program, promoter, max,
+    /// answer and a 32 bytes salt
+    Salted,
+}
+
+impl CommitmentScheme {
+    pub fn from_u8(value: &u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(CommitmentScheme::Legacy),
+            1 => Ok(CommitmentScheme::Salted),
+            _ => Err(ProgramError::InvalidAccountData),
         }
     }
 }
@@ -60,43 +145,216 @@ pub struct GameRecord {
     pub answer: u16,
     /// the random string
     pub random_string: [u8; 6],
+    /// where the game is in its lifecycle
+    pub status: GameStatus,
+    /// how the answer was committed to at creation
+    pub commitment: CommitmentScheme,
+    /// the bet in lamports when the game was created, 0 for games created
+    /// before the config existed, see `stake`
+    pub bet: u64,
+    /// seconds the promoter has to reveal once joined, 0 for games created
+    /// before it was stored
+    pub reveal_time: i64,
+    /// last timestamp the answer can be revealed at, set when the player
+    /// joins, see `deadline`
+    pub reveal_deadline: i64,
+    /// when the promoter revealed, 0 until then and for games revealed
+    /// before it was stored
+    pub revealed_at: i64,
+    /// last timestamp the game can be joined at, 0 for no expiry
+    pub open_until: i64,
+    /// bump of the game PDA, 0 for games created before it was stored,
+    /// see `utils::check_game_key`
+    pub bump: u8,
+    /// settlement fee in basis points when the game was created, see `fee`
+    pub fee_bps: u16,
 }
 
 impl Sealed for GameRecord {}
 
 impl GameRecord {
-    pub fn new(promoter: Pubkey, max: u16, odds: u32, level: u8) -> Self {
+    pub fn new(promoter: Pubkey, max: u16, odds: u32, level: GameLevel, bet: u64) -> Self {
         let mut arr = [0u8; 6];
         arr.copy_from_slice("000000".as_bytes());
 
-        let game_level = GameLevel::from_u8(&level).unwrap();
-
         Self {
             promoter,
             player: crate::ID,     // default program_id 
             firing_point: 0,
             max,
             odds_x100: odds,
-            level: game_level,   
+            level,
             shot_time: 0,
             answer: 0,
             random_string: arr,
+            status: GameStatus::Open,
+            commitment: CommitmentScheme::Salted,
+            bet,
+            reveal_time: 0,
+            reveal_deadline: 0,
+            revealed_at: 0,
+            open_until: 0,
+            bump: 0,
+            fee_bps: 0,
         }
     }
-    
+
+    /// What the player has to put in, fixed when the game was created.
+    pub fn stake(&self) -> u64 {
+        match self.bet {
+            0 => self.level.get_bet(),
+            bet => bet,
+        }
+    }
+
+    /// The fee fixed when the game was created, games created before the
+    /// config existed (`bet` 0) pay `config_fee_bps`.
+    pub fn fee(&self, config_fee_bps: u16) -> u16 {
+        match self.bet {
+            0 => config_fee_bps,
+            _ => self.fee_bps,
+        }
+    }
+
+    /// Lamports of the game account held back from the escrow for its rent
+    /// exemption. v1 counted the whole balance as escrow, so a migrated game
+    /// (`bet` 0) only holds back the top up `Migrate` paid.
+    pub fn rent_reserve(&self, rent: &Rent, data_len: usize) -> u64 {
+        match self.bet {
+            0 => rent.minimum_balance(data_len).saturating_sub(rent.minimum_balance(Self::V1_LEN)),
+            _ => rent.minimum_balance(data_len),
+        }
+    }
+
+    /// Fixes the reveal deadline of a game joined at `now`, games created
+    /// before they had a reveal time use `config_reveal_time`.
+    pub fn set_reveal_deadline(&mut self, now: i64, config_reveal_time: i64) -> Result<(), ProgramError> {
+        let reveal_time = match self.reveal_time {
+            0 => config_reveal_time,
+            reveal_time => reveal_time,
+        };
+        self.reveal_deadline = now.checked_add(reveal_time).ok_or(GameError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// The stored reveal deadline, games joined before it was stored
+    /// count `config_reveal_time` from `shot_time`.
+    pub fn deadline(&self, config_reveal_time: i64) -> Result<i64, ProgramError> {
+        match self.reveal_deadline {
+            0 => Ok(self.shot_time.checked_add(config_reveal_time).ok_or(GameError::MathOverflow)?),
+            deadline => Ok(deadline),
+        }
+    }
+
+    /// Whether the game can no longer be joined at `now`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.open_until != 0 && now > self.open_until
+    }
+
+    /// Whether anyone, not only the player or the promoter, can settle the
+    /// game at `now`: once the reveal `deadline` passed, or `grace_time`
+    /// after the promoter revealed.
+    pub fn is_open_to_settle(&self, now: i64, deadline: i64, grace_time: i64) -> Result<bool, ProgramError> {
+        if now > deadline {
+            return Ok(true);
+        }
+
+        match self.revealed_at {
+            0 => Ok(false),
+            revealed_at => Ok(now > revealed_at.checked_add(grace_time).ok_or(GameError::MathOverflow)?),
+        }
+    }
+
+    /// Moves the game to `next`, failing if the lifecycle doesn't allow it.
+    pub fn transition(&mut self, next: GameStatus) -> Result<(), ProgramError> {
+        if let Err(e) = self.status.check_transition(next) {
+            msg!("can't move game from {:?} to {:?}", self.status, next);
+            return Err(e.into());
+        }
+        self.status = next;
+
+        Ok(())
+    }
 }
 
-/// total size = 89 bytes
+/// v2 layout, total size = 156 bytes
+///
+/// | header (2) | v1 fields (89) | extension (65) |
+///
+/// The extension holds `status`, `commitment`, `bet`, `reveal_time`,
+/// `reveal_deadline`, `revealed_at`, `open_until`, `bump`, `fee_bps` and
+/// then reserved zeroes. New fields are carved out of the reserved tail and
+/// must treat all-zero as "unset", so growing the record doesn't require
+/// another migration.
 impl Pack for GameRecord {
-    const LEN: usize = 32 + 32 + 2 + 2 + 4 + 1 + 8 + 2 + 6;
-    
+    const LEN: usize = AccountHeader::LEN + Self::V1_LEN + Self::EXTENSION_LEN;
+
+    /// Panics on a too small `dst`, processors use `try_pack_into_slice`.
     fn pack_into_slice(&self, dst: &mut [u8]) {
+        self.try_pack_into_slice(dst).expect("destination slice too small for GameRecord");
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Self::LEN {
+            msg!("Source slice too small for GameRecord");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        AccountHeader::unpack_from_slice(src)?.check(AccountTag::GameRecord, Self::VERSION)?;
+
+        let mut record = Self::unpack_fields(&src[AccountHeader::LEN..])?;
+        let mut offset = AccountHeader::LEN + Self::V1_LEN;
+
+        record.status = GameStatus::from_u8(&src[offset])?;
+        offset += 1;
+
+        record.commitment = CommitmentScheme::from_u8(&src[offset])?;
+        offset += 1;
+
+        record.bet = u64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        record.reveal_time = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        record.reveal_deadline = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        record.revealed_at = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        record.open_until = i64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        record.bump = src[offset];
+        offset += 1;
+
+        record.fee_bps = u16::from_le_bytes(read(src, offset)?);
+
+        Ok(record)
+    }
+}
+
+impl GameRecord {
+    /// current layout version
+    pub const VERSION: u8 = 2;
+    /// size of the original, header-less layout
+    pub const V1_LEN: usize = 32 + 32 + 2 + 2 + 4 + 1 + 8 + 2 + 6;
+
+    const EXTENSION_LEN: usize = 65;
+
+    /// Writes the record into `dst`, which is left untouched if it's too small.
+    pub fn try_pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
         if dst.len() < Self::LEN {
-            msg!("Destination slice too small for GameRecord");
-            return;
+            msg!("Destination slice too small for GameRecord: {} < {}", dst.len(), Self::LEN);
+            return Err(ProgramError::AccountDataTooSmall);
         }
 
-        let mut offset = 0;
+        AccountHeader::new(AccountTag::GameRecord, Self::VERSION)
+            .pack_into_slice(&mut dst[..AccountHeader::LEN]);
+
+        let mut offset = AccountHeader::LEN;
 
         dst[offset..offset + 32].copy_from_slice(self.promoter.as_ref());
         offset += 32;
@@ -124,48 +382,100 @@ impl Pack for GameRecord {
         offset += 2;
 
         dst[offset..offset + 6].copy_from_slice(&self.random_string);
+        offset += 6;
+
+        dst[offset] = self.status as u8;
+        offset += 1;
+
+        dst[offset] = self.commitment as u8;
+        offset += 1;
+
+        dst[offset..offset + 8].copy_from_slice(&self.bet.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.reveal_time.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.reveal_deadline.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.revealed_at.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.open_until.to_le_bytes());
+        offset += 8;
+
+        dst[offset] = self.bump;
+        offset += 1;
+
+        dst[offset..offset + 2].copy_from_slice(&self.fee_bps.to_le_bytes());
+        offset += 2;
+
+        dst[offset..Self::LEN].fill(0);
+
+        Ok(())
     }
 
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if src.len() < Self::LEN {
-            msg!("Source slice too small for GameRecord");
+    /// Reads an account written with the original 89 bytes layout.
+    ///
+    /// v1 had no status byte, so it is recovered from the sentinel values
+    /// the old processors relied on. v1 reveals took any answer, one out of
+    /// `1..=max` stays `Revealed`: the game settles under the v1 rules it was
+    /// played under, where the player only wins on the exact answer.
+    pub fn unpack_v1(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::V1_LEN {
+            msg!("Source slice isn't a v1 GameRecord");
             return Err(ProgramError::InvalidAccountData);
         }
 
+        let mut record = Self::unpack_fields(src)?;
+        if record.promoter == Pubkey::default() {
+            msg!("v1 GameRecord has been cleared");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        record.status = if record.firing_point == 0 {
+            GameStatus::Open
+        } else if record.answer == 0 {
+            GameStatus::Joined
+        } else {
+            GameStatus::Revealed
+        };
+
+        Ok(record)
+    }
+
+    /// Reads the fields shared by every layout version, leaving the status `Open`
+    /// and the commitment `Legacy`.
+    fn unpack_fields(src: &[u8]) -> Result<Self, ProgramError> {
         let mut offset = 0;
 
-        let promoter = Pubkey::new_from_array(src[offset..offset + 32].try_into().unwrap());
+        let promoter = Pubkey::new_from_array(read(src, offset)?);
         offset += 32;
 
-        let player = Pubkey::new_from_array(src[offset..offset + 32].try_into().unwrap());
+        let player = Pubkey::new_from_array(read(src, offset)?);
         offset += 32;
 
-        let firing_point = u16::from_le_bytes(src[offset..offset + 2].try_into().unwrap());
+        let firing_point = u16::from_le_bytes(read(src, offset)?);
         offset += 2;
 
-        let max = u16::from_le_bytes(src[offset..offset + 2].try_into().unwrap());
+        let max = u16::from_le_bytes(read(src, offset)?);
         offset += 2;
 
-        let odds_x100 = u32::from_le_bytes(src[offset..offset + 4].try_into().unwrap());
+        let odds_x100 = u32::from_le_bytes(read(src, offset)?);
         offset += 4;
 
-        let level = match src[offset] {
-            0 => GameLevel::S,
-            1 => GameLevel::A,
-            2 => GameLevel::B,
-            3 => GameLevel::C,
-            4 => GameLevel::D,
-            _ => return Err(ProgramError::InvalidAccountData),
-        };
+        let [level] = read(src, offset)?;
+        let level = GameLevel::from_u8(&level).map_err(|_| ProgramError::InvalidAccountData)?;
         offset += 1;
 
-        let shot_time = i64::from_le_bytes(src[offset..offset + 8].try_into().unwrap());
+        let shot_time = i64::from_le_bytes(read(src, offset)?);
         offset += 8;
 
-        let answer = u16::from_le_bytes(src[offset..offset + 2].try_into().unwrap());
+        let answer = u16::from_le_bytes(read(src, offset)?);
         offset += 2;
 
-        let random_string = src[offset..offset + 6].try_into().unwrap();
+        let random_string = read(src, offset)?;
 
         Ok(Self {
             promoter,
@@ -177,6 +487,15 @@ impl Pack for GameRecord {
             shot_time,
             answer,
             random_string,
+            status: GameStatus::Open,
+            commitment: CommitmentScheme::Legacy,
+            bet: 0,
+            reveal_time: 0,
+            reveal_deadline: 0,
+            revealed_at: 0,
+            open_until: 0,
+            bump: 0,
+            fee_bps: 0,
         })
     }
 }
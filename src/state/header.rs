@@ -0,0 +1,72 @@
+use solana_program::program_error::ProgramError;
+
+use crate::{error::GameError, msg};
+
+/// Discriminates the kinds of accounts owned by the program.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountTag {
+    Uninitialized,
+    GameRecord,
+    Config,
+    Vault,
+}
+
+impl AccountTag {
+    pub fn from_u8(value: &u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(AccountTag::Uninitialized),
+            1 => Ok(AccountTag::GameRecord),
+            2 => Ok(AccountTag::Config),
+            3 => Ok(AccountTag::Vault),
+            _ => Err(GameError::WrongAccountType.into()),
+        }
+    }
+}
+
+/// Leading bytes of every program account: what it is and which layout it uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountHeader {
+    pub tag: AccountTag,
+    pub version: u8,
+}
+
+impl AccountHeader {
+    pub const LEN: usize = 1 + 1;
+
+    pub fn new(tag: AccountTag, version: u8) -> Self {
+        Self { tag, version }
+    }
+
+    pub fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.tag as u8;
+        dst[1] = self.version;
+    }
+
+    pub fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Self::LEN {
+            msg!("Source slice too small for AccountHeader");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            tag: AccountTag::from_u8(&src[0])?,
+            version: src[1],
+        })
+    }
+
+    /// Fails unless the header describes an account of `tag` at layout `version`.
+    pub fn check(&self, tag: AccountTag, version: u8) -> Result<(), ProgramError> {
+        if self.tag != tag {
+            msg!("Wrong account type: {:?} should be {:?}", self.tag, tag);
+            return Err(GameError::WrongAccountType.into());
+        }
+
+        if self.version != version {
+            msg!("Unsupported {:?} version: {} should be {}", tag, self.version, version);
+            return Err(GameError::UnsupportedAccountVersion.into());
+        }
+
+        Ok(())
+    }
+}
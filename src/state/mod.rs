@@ -1,3 +1,13 @@
+use solana_program::program_error::ProgramError;
 
+pub mod config;
+pub mod game;
+pub mod header;
+pub mod vault;
 
-pub mod game;
\ No newline at end of file
+/// The `N` bytes of `src` at `offset`.
+pub(crate) fn read<const N: usize>(src: &[u8], offset: usize) -> Result<[u8; N], ProgramError> {
+    src.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(ProgramError::InvalidAccountData)
+}
@@ -0,0 +1,192 @@
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::GameError,
+    msg,
+    state::{header::{AccountHeader, AccountTag}, read},
+};
+
+/// Most signers a withdraw authority can have
+pub const MAX_WITHDRAW_SIGNERS: usize = 5;
+
+/// The fee vault, stored in the PDA at `utils::get_vault_key`
+///
+/// Its lamports above the rent exemption are the fees not yet withdrawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vault {
+    /// how many of `signers` must sign a withdrawal
+    pub threshold: u8,
+    /// who can withdraw, only the first `signer_count` are used
+    pub signers: [Pubkey; MAX_WITHDRAW_SIGNERS],
+    pub signer_count: u8,
+    /// every fee ever paid into the vault
+    pub fees_collected: u64,
+    /// every lamport ever withdrawn from the vault
+    pub fees_withdrawn: u64,
+}
+
+impl Sealed for Vault {}
+
+impl Vault {
+    /// current layout version
+    pub const VERSION: u8 = 1;
+
+    const RESERVED_LEN: usize = 64;
+
+    pub fn new(signers: &[Pubkey], threshold: u8) -> Result<Self, ProgramError> {
+        let mut vault = Self {
+            threshold: 0,
+            signers: [Pubkey::default(); MAX_WITHDRAW_SIGNERS],
+            signer_count: 0,
+            fees_collected: 0,
+            fees_withdrawn: 0,
+        };
+        vault.set_authority(signers, threshold)?;
+
+        Ok(vault)
+    }
+
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
+
+    /// Replaces the withdraw authority with `threshold` of `signers`.
+    pub fn set_authority(&mut self, signers: &[Pubkey], threshold: u8) -> Result<(), ProgramError> {
+        if signers.is_empty() || signers.len() > MAX_WITHDRAW_SIGNERS {
+            msg!("withdraw authority needs 1 to {} signers", MAX_WITHDRAW_SIGNERS);
+            return Err(GameError::InvalidWithdrawAuthority.into());
+        }
+
+        if threshold == 0 || threshold as usize > signers.len() {
+            msg!("threshold {} of {} signers", threshold, signers.len());
+            return Err(GameError::InvalidWithdrawAuthority.into());
+        }
+
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[..i].contains(signer) {
+                msg!("duplicated signer {}", signer);
+                return Err(GameError::InvalidWithdrawAuthority.into());
+            }
+        }
+
+        self.signers = [Pubkey::default(); MAX_WITHDRAW_SIGNERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+
+        Ok(())
+    }
+
+    /// Counts the distinct authority members among `signed`.
+    pub fn approvals<'a>(&self, signed: impl Iterator<Item = &'a Pubkey>) -> usize {
+        let mut seen = [false; MAX_WITHDRAW_SIGNERS];
+        for key in signed {
+            if let Some(i) = self.signers().iter().position(|s| s == key) {
+                seen[i] = true;
+            }
+        }
+
+        seen.iter().filter(|s| **s).count()
+    }
+
+    /// Fails with `InsufficientSigners` unless `threshold` members are among `signed`.
+    pub fn check_approvals<'a>(&self, signed: impl Iterator<Item = &'a Pubkey>) -> Result<(), ProgramError> {
+        let approvals = self.approvals(signed);
+
+        if approvals < self.threshold as usize {
+            msg!("{} of {} withdraw authority signatures", approvals, self.threshold);
+            return Err(GameError::InsufficientSigners.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// total size = 2 + 1 + 160 + 1 + 8 + 8 + 64 = 244 bytes
+impl Pack for Vault {
+    const LEN: usize = AccountHeader::LEN + 1 + 32 * MAX_WITHDRAW_SIGNERS + 1 + 8 + 8 + Self::RESERVED_LEN;
+
+    /// Panics on a too small `dst`, processors use `try_pack_into_slice`.
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        self.try_pack_into_slice(dst).expect("destination slice too small for Vault");
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Self::LEN {
+            msg!("Source slice too small for Vault");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        AccountHeader::unpack_from_slice(src)?.check(AccountTag::Vault, Self::VERSION)?;
+
+        let mut offset = AccountHeader::LEN;
+
+        let threshold = src[offset];
+        offset += 1;
+
+        let mut signers = [Pubkey::default(); MAX_WITHDRAW_SIGNERS];
+        for signer in signers.iter_mut() {
+            *signer = Pubkey::new_from_array(read(src, offset)?);
+            offset += 32;
+        }
+
+        let signer_count = src[offset];
+        offset += 1;
+        if signer_count as usize > MAX_WITHDRAW_SIGNERS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let fees_collected = u64::from_le_bytes(read(src, offset)?);
+        offset += 8;
+
+        let fees_withdrawn = u64::from_le_bytes(read(src, offset)?);
+
+        Ok(Self {
+            threshold,
+            signers,
+            signer_count,
+            fees_collected,
+            fees_withdrawn,
+        })
+    }
+}
+
+impl Vault {
+    /// Writes the vault into `dst`, which is left untouched if it's too small.
+    pub fn try_pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < Self::LEN {
+            msg!("Destination slice too small for Vault: {} < {}", dst.len(), Self::LEN);
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        AccountHeader::new(AccountTag::Vault, Self::VERSION)
+            .pack_into_slice(&mut dst[..AccountHeader::LEN]);
+
+        let mut offset = AccountHeader::LEN;
+
+        dst[offset] = self.threshold;
+        offset += 1;
+
+        for signer in self.signers.iter() {
+            dst[offset..offset + 32].copy_from_slice(signer.as_ref());
+            offset += 32;
+        }
+
+        dst[offset] = self.signer_count;
+        offset += 1;
+
+        dst[offset..offset + 8].copy_from_slice(&self.fees_collected.to_le_bytes());
+        offset += 8;
+
+        dst[offset..offset + 8].copy_from_slice(&self.fees_withdrawn.to_le_bytes());
+        offset += 8;
+
+        dst[offset..Self::LEN].fill(0);
+
+        Ok(())
+    }
+}
@@ -1,37 +1,103 @@
+use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
 use solana_program::hash::Hash;
 use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::sysvar::Sysvar;
 use solana_program::{hash::hashv};
+use web3_utils::check::{check_account_key, check_account_owner};
 
-use crate::constants::REVEAL_TIME;
+use crate::constants::{BPF_LOADER_UPGRADEABLE_ID, COMMITMENT_TAG, CONFIG_BUMP, CONFIG_KEY, GAME_SEED, SYSTEM_ID, VAULT_BUMP, VAULT_KEY};
+use crate::msg;
+use crate::error::GameError;
+use crate::state::config::Config;
+use crate::state::game::GameRecord;
+use crate::state::vault::Vault;
 
 
-pub fn get_seeds_and_key(
-    hashed_value: Vec<u8>, 
-) -> (Pubkey, Vec<u8>) {
-    let mut seeds_vec: Vec<u8> = hashed_value;
+/// Seeds of the game PDA committed to by `hash`, without allocating.
+pub fn get_game_seeds<'a>(hash: &'a [u8; 32], bump: &'a [u8; 1]) -> [&'a [u8]; 3] {
+    [hash, &GAME_SEED, bump]
+}
+
+/// Searches the game PDA committed to by `hash` and its bump, for clients
+/// and games created before the bump was stored.
+pub fn find_game_key(hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[hash, &GAME_SEED], &crate::ID)
+}
+
+/// Checks that `key` is the game PDA committed to by `hash`, with a single
+/// `create_program_address` when the `bump` is known (not 0).
+pub fn check_game_key(key: &Pubkey, hash: &[u8; 32], bump: u8) -> ProgramResult {
+    let game = match bump {
+        0 => find_game_key(hash).0,
+        bump => Pubkey::create_program_address(&get_game_seeds(hash, &[bump]), &crate::ID)
+            .map_err(|_| GameError::InvalidGameAccount)?,
+    };
+
+    if key != &game {
+        msg!("game account doesn't match the commitment");
+        return Err(GameError::InvalidGameAccount.into());
+    }
+
+    Ok(())
+}
+
+/// Fails with `GameClosed` once `close_game_account` ran on the address.
+pub fn check_game_not_closed(
+    game_recorder: &AccountInfo,
+) -> ProgramResult {
+    if game_recorder.owner == &SYSTEM_ID && game_recorder.data_is_empty() {
+        msg!("Game {} is closed", game_recorder.key);
+        return Err(GameError::GameClosed.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that an existing game record is owned by this program and has the record layout.
+pub fn check_game_recorder(
+    game_recorder: &AccountInfo,
+) -> ProgramResult {
+    check_game_not_closed(game_recorder)?;
+    check_account_owner(game_recorder, &crate::ID)?;
 
-    let hash_byte = hashv(&[("game").as_bytes()])
-        .as_ref()
-        .to_vec();
+    if game_recorder.data_len() == GameRecord::V1_LEN {
+        msg!("Game record still uses the v1 layout");
+        return Err(GameError::AccountNeedsMigration.into());
+    }
 
-    for b in hash_byte {
-        seeds_vec.push(b);
+    if game_recorder.data_len() != GameRecord::LEN {
+        msg!("Wrong game record size: {} should be {}", game_recorder.data_len(), GameRecord::LEN);
+        return Err(GameError::InvalidGameAccount.into());
     }
 
-    let (pda, bump) =
-        Pubkey::find_program_address(&seeds_vec.chunks(32).collect::<Vec<&[u8]>>(), &crate::ID);
-    seeds_vec.push(bump);
+    Ok(())
+}
+
+/// Sends every lamport left in a game account to `recipient`, then shrinks
+/// it to zero bytes and hands it back to the system program so the address
+/// can't be read as a `GameRecord` anymore.
+pub fn close_game_account(
+    game_recorder: &AccountInfo,
+    recipient: &AccountInfo,
+) -> ProgramResult {
+    let lamports = game_recorder.lamports();
+    **game_recorder.try_borrow_mut_lamports()? -= lamports;
+    **recipient.try_borrow_mut_lamports()? += lamports;
+
+    game_recorder.resize(0)?;
+    game_recorder.assign(&SYSTEM_ID);
 
-    (pda, seeds_vec)
+    Ok(())
 }
 
 pub fn get_splicing_hash(
     x: u16,
     random: [u8; 6],
-) -> Vec<u8> {
+) -> [u8; 32] {
 
     let x_bytes = x.to_le_bytes();
     let x_hash: Hash = hashv(&[&x_bytes]);
@@ -40,21 +106,102 @@ pub fn get_splicing_hash(
 
     let combined_hash: Hash = hashv(&[x_hash.as_ref(), random_hash.as_ref()]);
 
-    combined_hash.as_ref().to_vec()
+    combined_hash.to_bytes()
+}
+
+/// Commitment of the salted scheme, bound to this program, the promoter and the game's max.
+pub fn get_commitment_hash(
+    promoter: &Pubkey,
+    max: u16,
+    x: u16,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    hashv(&[
+        COMMITMENT_TAG,
+        crate::ID.as_ref(),
+        promoter.as_ref(),
+        &max.to_le_bytes(),
+        &x.to_le_bytes(),
+        salt,
+    ])
+    .to_bytes()
+}
+
+/// The config PDA, `find_program_address(&[CONFIG_SEED])` worked out ahead.
+pub fn get_config_key() -> (Pubkey, u8) {
+    (CONFIG_KEY, CONFIG_BUMP)
 }
 
+/// Checks the config account is the program's config PDA and reads it.
+pub fn load_config(
+    config: &AccountInfo,
+) -> Result<Config, ProgramError> {
+    check_account_owner(config, &crate::ID)?;
+
+    if config.key != &get_config_key().0 {
+        msg!("Wrong config account: {}", config.key);
+        return Err(GameError::WrongAccountType.into());
+    }
+
+    let data = config.try_borrow_data()?;
+    Config::unpack_from_slice(&data)
+}
+
+/// The vault PDA, `find_program_address(&[VAULT_SEED])` worked out ahead.
+pub fn get_vault_key() -> (Pubkey, u8) {
+    (VAULT_KEY, VAULT_BUMP)
+}
+
+/// Checks the vault account is the program's vault PDA and reads it.
+pub fn load_vault(
+    vault: &AccountInfo,
+) -> Result<Vault, ProgramError> {
+    check_account_owner(vault, &crate::ID)?;
+
+    if vault.key != &get_vault_key().0 {
+        msg!("Wrong vault account: {}", vault.key);
+        return Err(GameError::WrongAccountType.into());
+    }
+
+    let data = vault.try_borrow_data()?;
+    Vault::unpack_from_slice(&data)
+}
+
+/// The program's `ProgramData` account, owned by the upgradeable loader.
+pub fn get_program_data_key() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[crate::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID)
+}
+
+/// Reads the upgrade authority out of the program's `ProgramData` account.
+pub fn get_upgrade_authority(
+    program_data: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    check_account_key(program_data, &get_program_data_key().0)?;
+    check_account_owner(program_data, &BPF_LOADER_UPGRADEABLE_ID)?;
+
+    // bincode of UpgradeableLoaderState::ProgramData:
+    // tag u32 (3) | slot u64 | Option<Pubkey>
+    let data = program_data.try_borrow_data()?;
+    if data.len() < 45 || data[0..4] != 3u32.to_le_bytes() {
+        msg!("Not a ProgramData account");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    match data[12] {
+        0 => Ok(None),
+        _ => Ok(Some(Pubkey::new_from_array(data[13..45].try_into().unwrap()))),
+    }
+}
+
+/// Whether the reveal `deadline` hasn't passed yet.
 pub fn if_reveal_time(
-    record_time: i64
+    deadline: i64,
 ) -> Result<bool, ProgramError> {
     let now = Clock::get()?.unix_timestamp;
 
-    if record_time + REVEAL_TIME >= now {
+    if deadline >= now {
         Ok(true)
     }else {
         Ok(false)
     }
 }
-
-pub fn percent_of(value: u64, percent: u64) -> u64 {
-    value.saturating_mul(percent).saturating_div(100)
-}
\ No newline at end of file
@@ -0,0 +1,68 @@
+mod common;
+
+use common::{assert_game_error, start, Env, TestGame, LEVEL, ODDS_X100};
+use digital_sol_game::error::GameError;
+use solana_sdk::signature::Signer;
+
+async fn assert_closed(env: &mut Env, game: &TestGame) {
+    let account = env.context.banks_client.get_account(game.key).await.unwrap();
+    assert!(account.is_none(), "game account still exists: {:?}", account);
+
+    let result = env.participate(game, 3).await;
+    assert_game_error(result, GameError::GameClosed);
+
+    let result = env.reveal(game).await;
+    assert_game_error(result, GameError::GameClosed);
+
+    let result = env.end(game).await;
+    assert_game_error(result, GameError::GameClosed);
+
+    let result = env.delete(game).await;
+    assert_game_error(result, GameError::GameClosed);
+}
+
+#[tokio::test]
+async fn settled_game_is_closed() {
+    let mut env = start().await;
+    let game = env.joined(3, 5).await;
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    assert_closed(&mut env, &game).await;
+}
+
+#[tokio::test]
+async fn deleted_game_is_closed() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    env.delete(&game).await.unwrap();
+
+    assert_closed(&mut env, &game).await;
+}
+
+#[tokio::test]
+async fn closed_game_cant_be_used_in_the_same_transaction() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    let player_key = env.player.pubkey();
+    let instructions = [env.delete_ix(&game), env.participate_ix(&player_key, &game, 3)];
+    let promoter = env.promoter.insecure_clone();
+    let player = env.player.insecure_clone();
+    let result = env.send_all(&instructions, &[&promoter, &player]).await;
+    assert_game_error(result, GameError::GameClosed);
+}
+
+#[tokio::test]
+async fn deleted_game_address_can_be_reused() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    env.delete(&game).await.unwrap();
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    env.participate(&game, 3).await.unwrap();
+    assert_eq!(env.record(&game.key).await.player, env.player.pubkey());
+}
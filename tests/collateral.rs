@@ -0,0 +1,91 @@
+mod common;
+
+use common::{amounts, assert_game_error, config_params, start, FEE_BPS, LEVEL, ODDS_X100, START_LAMPORTS};
+use digital_sol_game::{
+    error::GameError,
+    processor::create_game,
+    state::game::GameLevel,
+};
+use solana_sdk::{rent::Rent, signature::Signer};
+
+#[tokio::test]
+async fn create_locks_the_previewed_collateral() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    let config = env.config().await;
+
+    let preview = create_game::Params {
+        max_number: game.max,
+        odds_x100: ODDS_X100,
+        splicing_hash: game.hash,
+        game_level: LEVEL as u8,
+        reveal_time: 0,
+        open_until: 0,
+    }
+    .collateral(&config, &Rent::default())
+    .unwrap();
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    assert_eq!(preview, amounts().collateral);
+    assert_eq!(env.lamports(&game.key).await, preview);
+}
+
+#[tokio::test]
+async fn level_s_no_longer_needs_a_hundredfold_collateral() {
+    let mut env = start().await;
+    let game = env.game(u16::MAX, 3);
+
+    // the old `bet * max_number * 100` is far above what the promoter owns
+    env.create(&game, 50, GameLevel::S).await.unwrap();
+    assert!(env.lamports(&game.key).await < START_LAMPORTS);
+}
+
+#[tokio::test]
+async fn participate_rejects_a_game_that_cant_pay() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    let record = env.record(&game.key).await;
+    let collateral = env.lamports(&game.key).await;
+    env.set_record(&game.key, &record, collateral - 1);
+
+    let result = env.participate(&game, 3).await;
+    assert_game_error(result, GameError::InsufficientEscrow);
+}
+
+#[tokio::test]
+async fn fee_is_fixed_when_the_game_is_created() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    assert_eq!(env.record(&game.key).await.fee_bps, FEE_BPS);
+
+    let mut params = config_params();
+    params.fee_bps = FEE_BPS * 5;
+    env.update_config(params).await.unwrap();
+
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+    env.participate(&game, 3).await.unwrap();
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    assert_eq!(env.lamports(&vault).await, vault_before + amounts().fee);
+}
+
+#[tokio::test]
+async fn promoter_gets_the_rent_back_on_settlement() {
+    let mut env = start().await;
+    let game = env.joined(3, 3).await;
+    env.reveal(&game).await.unwrap();
+
+    let promoter_key = env.promoter.pubkey();
+    let escrow = env.lamports(&game.key).await;
+    let promoter_before = env.lamports(&promoter_key).await;
+    env.end(&game).await.unwrap();
+
+    assert_eq!(env.lamports(&promoter_key).await, promoter_before + escrow - amounts().win);
+    assert_eq!(env.lamports(&game.key).await, 0);
+}
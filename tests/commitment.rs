@@ -0,0 +1,107 @@
+mod common;
+
+use common::{assert_game_error, start, Env, TestGame, LEVEL, ODDS_X100};
+use digital_sol_game::{
+    error::GameError,
+    instruction,
+    processor::reveal::Secret,
+    state::game::{CommitmentScheme, GameRecord, GameStatus},
+    utils::{get_commitment_hash, get_game_seeds},
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+#[test]
+fn commitment_is_bound_to_promoter_and_max() {
+    let promoter = Pubkey::new_unique();
+    let salt = [7; 32];
+    let commitment = get_commitment_hash(&promoter, 10, 3, &salt);
+
+    assert_ne!(commitment, get_commitment_hash(&Pubkey::new_unique(), 10, 3, &salt));
+    assert_ne!(commitment, get_commitment_hash(&promoter, 11, 3, &salt));
+    assert_ne!(commitment, get_commitment_hash(&promoter, 10, 4, &salt));
+    assert_ne!(commitment, get_commitment_hash(&promoter, 10, 3, &[8; 32]));
+}
+
+#[tokio::test]
+async fn salted_game_is_revealable() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    assert_eq!(env.record(&game.key).await.commitment, CommitmentScheme::Salted);
+
+    env.participate(&game, 5).await.unwrap();
+    env.reveal(&game).await.unwrap();
+
+    let record = env.record(&game.key).await;
+    assert_eq!(record.answer, 3);
+    assert_eq!(record.status, GameStatus::Revealed);
+}
+
+#[tokio::test]
+async fn wrong_answer_or_salt_is_rejected() {
+    let mut env = start().await;
+    let game = env.joined(3, 5).await;
+
+    let result = env.reveal_with(&game, 5, game.secret.clone()).await;
+    assert_game_error(result, GameError::InvalidGameAccount);
+
+    let result = env.reveal_with(&game, 3, Secret::Salted { salt: [0; 32] }).await;
+    assert_game_error(result, GameError::InvalidGameAccount);
+}
+
+#[tokio::test]
+async fn legacy_secret_is_rejected_on_salted_game() {
+    let mut env = start().await;
+    let game = env.joined(3, 5).await;
+
+    let result = env.reveal_with(&game, 3, Secret::Legacy { random: *b"abcdef" }).await;
+    assert_game_error(result, GameError::WrongCommitmentScheme);
+}
+
+#[tokio::test]
+async fn legacy_game_remains_revealable() {
+    let mut env = start().await;
+    let game = TestGame::legacy(10, 3, *b"abcdef");
+
+    let mut record = GameRecord::new(env.promoter.pubkey(), game.max, ODDS_X100, LEVEL, 0);
+    record.commitment = CommitmentScheme::Legacy;
+    record.player = env.player.pubkey();
+    record.firing_point = 5;
+    record.shot_time = env.now().await;
+    record.status = GameStatus::Joined;
+    env.set_record(&game.key, &record, 1_000_000_000);
+
+    env.reveal(&game).await.unwrap();
+
+    let record = env.record(&game.key).await;
+    assert_eq!(record.answer, 3);
+    assert_eq!(record.random_string, *b"abcdef");
+    assert_eq!(record.status, GameStatus::Revealed);
+}
+
+#[tokio::test]
+async fn game_stores_its_canonical_bump() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    assert_eq!(env.record(&game.key).await.bump, game.bump);
+}
+
+#[tokio::test]
+async fn non_canonical_game_address_is_rejected() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    let key = (1..game.bump)
+        .rev()
+        .find_map(|bump| Pubkey::create_program_address(&get_game_seeds(&game.hash, &[bump]), &digital_sol_game::ID).ok())
+        .unwrap();
+    let mut instruction = instruction::create_game(&env.promoter.pubkey(), Env::create_params(&game, ODDS_X100, LEVEL));
+    instruction.accounts[1].pubkey = key;
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.send(instruction, &[&promoter]).await;
+    assert_game_error(result, GameError::InvalidGameAccount);
+}
@@ -0,0 +1,693 @@
+#![allow(dead_code)]
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use digital_sol_game::{
+    constants::BPF_LOADER_UPGRADEABLE_ID,
+    entrypoint::process_instruction,
+    error::GameError,
+    events::{decode_logs, GameEvent},
+    instruction,
+    processor::{
+        create_game, init_config,
+        reveal::{self, Secret},
+        set_withdraw_authority, update_config,
+    },
+    state::{config::Config, game::{GameLevel, GameRecord}, vault::Vault},
+    utils::{find_game_key, get_commitment_hash, get_config_key, get_splicing_hash, get_vault_key},
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader,
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use solana_system_interface::program as system_program;
+use std::{
+    path::{Path, PathBuf},
+    sync::Once,
+};
+
+pub const START_LAMPORTS: u64 = 100_000_000_000;
+pub const FEE_BPS: u16 = 100;
+/// Reveal time of the test config, 100 minutes.
+pub const REVEAL_TIME: i64 = 6000;
+pub const MIN_REVEAL_TIME: i64 = 60;
+pub const MAX_REVEAL_TIME: i64 = REVEAL_TIME * 10;
+pub const CRANK_REWARD_BPS: u16 = 1_000;
+pub const SETTLE_GRACE_TIME: i64 = 600;
+/// Odds and level of the games most tests play.
+pub const ODDS_X100: u32 = 200;
+pub const LEVEL: GameLevel = GameLevel::D;
+
+/// Expected lamports of a `LEVEL` game at `ODDS_X100`.
+pub struct Amounts {
+    pub bet: u64,
+    pub win: u64,
+    pub fee: u64,
+    /// Part of `fee` paid to whoever settles the game.
+    pub reward: u64,
+    pub rent: u64,
+    /// What the promoter escrows when creating the game.
+    pub collateral: u64,
+}
+
+pub fn amounts() -> Amounts {
+    let bet = LEVEL.get_bet();
+    let win = bet * ODDS_X100 as u64 / 100;
+    let fee = win * FEE_BPS as u64 / 10_000;
+    let rent = Rent::default().minimum_balance(GameRecord::LEN);
+
+    Amounts {
+        bet,
+        win,
+        fee,
+        reward: fee * CRANK_REWARD_BPS as u64 / 10_000,
+        rent,
+        collateral: win + fee + rent,
+    }
+}
+
+pub struct TestGame {
+    pub max: u16,
+    pub answer: u16,
+    pub secret: Secret,
+    pub hash: [u8; 32],
+    pub key: Pubkey,
+    pub bump: u8,
+}
+
+impl TestGame {
+    /// A game committed with the salted scheme.
+    pub fn new(promoter: &Pubkey, max: u16, answer: u16) -> Self {
+        let salt = Keypair::new().pubkey().to_bytes();
+        let hash = get_commitment_hash(promoter, max, answer, &salt);
+        let (key, bump) = find_game_key(&hash);
+
+        Self { max, answer, secret: Secret::Salted { salt }, hash, key, bump }
+    }
+
+    /// A game committed with the original answer + 6 bytes random scheme.
+    pub fn legacy(max: u16, answer: u16, random: [u8; 6]) -> Self {
+        let hash = get_splicing_hash(answer, random);
+        let (key, bump) = find_game_key(&hash);
+
+        Self { max, answer, secret: Secret::Legacy { random }, hash, key, bump }
+    }
+}
+
+pub struct Env {
+    pub context: ProgramTestContext,
+    pub admin: Keypair,
+    pub vault: Pubkey,
+    pub promoter: Keypair,
+    pub player: Keypair,
+    /// Applied to every instruction sent, the identity unless benchmarking
+    /// an older build that reads another instruction format.
+    pub encode: fn(Instruction) -> Instruction,
+}
+
+/// The program with a fake `ProgramData` account making `upgrade_authority` its upgrade authority.
+pub fn program_test(upgrade_authority: &Pubkey) -> ProgramTest {
+    let program_test = ProgramTest::new(
+        "digital_sol_game",
+        digital_sol_game::ID,
+        processor!(process_instruction),
+    );
+    with_program_data(program_test, upgrade_authority)
+}
+
+/// `program_test` running the SBF build at `so` instead of the native
+/// processor, the runtime only meters the compute units of SBF programs.
+pub fn sbf_program_test(upgrade_authority: &Pubkey, so: &Path) -> ProgramTest {
+    let elf = std::fs::read(so)
+        .unwrap_or_else(|err| panic!("{}: {}, build it with `cargo build-sbf` first", so.display(), err));
+
+    let mut program_test = ProgramTest::default();
+    program_test.add_account(
+        digital_sol_game::ID,
+        Account {
+            lamports: Rent::default().minimum_balance(elf.len()),
+            data: elf,
+            owner: bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+    with_program_data(program_test, upgrade_authority)
+}
+
+/// Where `cargo build-sbf --sbf-out-dir target/deploy/<build>` puts the program.
+pub fn sbf_build(build: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target/deploy").join(build).join("digital_sol_game.so")
+}
+
+fn with_program_data(mut program_test: ProgramTest, upgrade_authority: &Pubkey) -> ProgramTest {
+    let mut program_data = vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    program_data.extend_from_slice(upgrade_authority.as_ref());
+    program_test.add_account(
+        program_data_key(),
+        Account {
+            lamports: 1_000_000_000,
+            data: program_data,
+            owner: BPF_LOADER_UPGRADEABLE_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test
+}
+
+pub fn program_data_key() -> Pubkey {
+    Pubkey::find_program_address(&[digital_sol_game::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+pub fn config_params() -> init_config::Params {
+    init_config::Params {
+        fee_bps: FEE_BPS,
+        reveal_time: REVEAL_TIME,
+        min_reveal_time: MIN_REVEAL_TIME,
+        max_reveal_time: MAX_REVEAL_TIME,
+        crank_reward_bps: CRANK_REWARD_BPS,
+        settle_grace_time: SETTLE_GRACE_TIME,
+        enabled_levels: 0b11111,
+        bets: [
+            GameLevel::S.get_bet(),
+            GameLevel::A.get_bet(),
+            GameLevel::B.get_bet(),
+            GameLevel::C.get_bet(),
+            GameLevel::D.get_bet(),
+        ],
+    }
+}
+
+pub fn system_account(lamports: u64) -> Account {
+    Account::new(lamports, 0, &system_program::id())
+}
+
+/// Starts a bank with funded promoter, player and admin, without a config.
+pub async fn start_without_config() -> Env {
+    start_with(program_test).await
+}
+
+/// Starts a bank running the SBF build at `so`, with the config initialized.
+pub async fn start_sbf(so: &Path, encode: fn(Instruction) -> Instruction) -> Env {
+    let mut env = start_with(|admin| sbf_program_test(admin, so)).await;
+    env.encode = encode;
+    env.init_config(config_params()).await.unwrap();
+    env
+}
+
+async fn start_with(program_test: impl FnOnce(&Pubkey) -> ProgramTest) -> Env {
+    let admin = Keypair::new();
+    let vault = get_vault_key().0;
+    let promoter = Keypair::new();
+    let player = Keypair::new();
+
+    let mut program_test = program_test(&admin.pubkey());
+    program_test.add_account(admin.pubkey(), system_account(START_LAMPORTS));
+    program_test.add_account(promoter.pubkey(), system_account(START_LAMPORTS));
+    program_test.add_account(player.pubkey(), system_account(START_LAMPORTS));
+
+    let context = program_test.start_with_context().await;
+    install_log_data_stubs();
+
+    Env {
+        context,
+        admin,
+        vault,
+        promoter,
+        player,
+        encode: |instruction| instruction,
+    }
+}
+
+/// Starts a bank with the config initialized from `config_params`.
+pub async fn start() -> Env {
+    let mut env = start_without_config().await;
+    env.init_config(config_params()).await.unwrap();
+    env
+}
+
+impl Env {
+    pub fn game(&self, max: u16, answer: u16) -> TestGame {
+        TestGame::new(&self.promoter.pubkey(), max, answer)
+    }
+
+    pub async fn send(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        self.send_all(&[instruction], signers).await
+    }
+
+    /// Sends `instructions` in a single transaction.
+    pub async fn send_all(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Sends `instruction`, which must succeed, and returns the events it logged.
+    pub async fn send_for_events(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Vec<GameEvent> {
+        let logs = self.send_for_logs(instruction, signers).await;
+        decode_logs(&logs)
+    }
+
+    /// Sends `instruction`, which must succeed, and returns its log lines.
+    pub async fn send_for_logs(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Vec<String> {
+        let (logs, _) = self.send_for_metadata(instruction, signers).await;
+        logs.iter()
+            .map(|line| match line.strip_prefix(LOG_DATA_PREFIX) {
+                Some(fields) => format!("Program data: {}", fields),
+                None => line.clone(),
+            })
+            .collect()
+    }
+
+    /// Sends `instruction`, which must succeed, and returns the compute units
+    /// it consumed, only metered on a `start_sbf` bank.
+    pub async fn send_for_units(&mut self, instruction: Instruction, signers: &[&Keypair]) -> u64 {
+        self.send_for_metadata(instruction, signers).await.1
+    }
+
+    /// Sends `instruction`, which must succeed, returns its log lines and compute units.
+    async fn send_for_metadata(&mut self, instruction: Instruction, signers: &[&Keypair]) -> (Vec<String>, u64) {
+        let transaction = self.transaction(&[instruction], signers).await;
+
+        // the previous transaction can still hold its account locks right
+        // after `process_transaction` returned
+        loop {
+            let banks_client = &mut self.context.banks_client;
+            let result = banks_client.process_transaction_with_metadata(transaction.clone()).await.unwrap();
+            match result.result {
+                Err(TransactionError::AccountInUse) => tokio::task::yield_now().await,
+                Err(err) => panic!("transaction failed: {:?}", err),
+                Ok(()) => {
+                    let metadata = result.metadata.unwrap();
+                    break (metadata.log_messages, metadata.compute_units_consumed);
+                }
+            }
+        }
+    }
+
+    /// Plays a game the promoter wins, returns the compute units of each of
+    /// its instructions.
+    pub async fn game_units(&mut self) -> Vec<(&'static str, u64)> {
+        let (promoter, player) = (self.promoter.insecure_clone(), self.player.insecure_clone());
+        // a game key found on the first bump, the search costs the same every run
+        let game = loop {
+            let game = self.game(10, 3);
+            if game.bump == u8::MAX {
+                break game;
+            }
+        };
+        let params = reveal::Params { x: game.answer, secret: game.secret.clone() };
+
+        let instructions = [
+            (
+                "CreateGame",
+                instruction::create_game(&promoter.pubkey(), Self::create_params(&game, ODDS_X100, LEVEL)),
+                &promoter,
+            ),
+            ("Participate", self.participate_ix(&player.pubkey(), &game, 5), &player),
+            ("Reveal", instruction::reveal(&promoter.pubkey(), &game.key, params), &promoter),
+            ("End", instruction::end(&player.pubkey(), &promoter.pubkey(), &player.pubkey(), &game.key), &player),
+        ];
+
+        let mut units = Vec::new();
+        for (name, instruction, signer) in instructions {
+            units.push((name, self.send_for_units(instruction, &[signer]).await));
+        }
+        units
+    }
+
+    async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let instructions: Vec<Instruction> = instructions.iter().cloned().map(self.encode).collect();
+
+        Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        )
+    }
+
+    pub async fn lamports(&mut self, key: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_account(*key)
+            .await
+            .unwrap()
+            .map(|account| account.lamports)
+            .unwrap_or(0)
+    }
+
+    pub async fn init_config(&mut self, params: init_config::Params) -> Result<(), BanksClientError> {
+        let admin = self.admin.insecure_clone();
+        self.init_config_as(&admin, params).await
+    }
+
+    pub async fn init_config_as(
+        &mut self,
+        admin: &Keypair,
+        params: init_config::Params,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction::init_config(&admin.pubkey(), params);
+        self.send(instruction, &[admin]).await
+    }
+
+    pub async fn update_config_as(
+        &mut self,
+        admin: &Keypair,
+        params: update_config::Params,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction::update_config(&admin.pubkey(), params);
+        self.send(instruction, &[admin]).await
+    }
+
+    pub async fn update_config(&mut self, params: update_config::Params) -> Result<(), BanksClientError> {
+        let admin = self.admin.insecure_clone();
+        self.update_config_as(&admin, params).await
+    }
+
+    pub async fn transfer_admin_as(
+        &mut self,
+        admin: &Keypair,
+        new_admin: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction::transfer_admin(&admin.pubkey(), &new_admin);
+        self.send(instruction, &[admin]).await
+    }
+
+    pub async fn withdraw_fees(
+        &mut self,
+        signers: &[&Keypair],
+        destination: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let signer_keys = signers.iter().map(|s| s.pubkey()).collect::<Vec<_>>();
+        let instruction = instruction::withdraw_fees(destination, &signer_keys, amount);
+        self.send(instruction, signers).await
+    }
+
+    /// `approvers` are members of the current withdraw authority.
+    pub async fn set_withdraw_authority_as(
+        &mut self,
+        approvers: &[&Keypair],
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<(), BanksClientError> {
+        let approver_keys = approvers.iter().map(|s| s.pubkey()).collect::<Vec<_>>();
+        let instruction = instruction::set_withdraw_authority(
+            &approver_keys,
+            set_withdraw_authority::Params { threshold, signers },
+        );
+        self.send(instruction, approvers).await
+    }
+
+    pub async fn vault_state(&mut self) -> Vault {
+        let account = self.context.banks_client.get_account(get_vault_key().0).await.unwrap().unwrap();
+        Vault::unpack_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn config(&mut self) -> Config {
+        let account = self.context.banks_client.get_account(get_config_key().0).await.unwrap().unwrap();
+        Config::unpack_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    /// Sets the bank's clock to `unix_timestamp`.
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Moves the bank's clock `seconds` forward.
+    pub async fn warp(&mut self, seconds: i64) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Writes a game record straight into the bank, bypassing the program.
+    pub fn set_record(&mut self, key: &Pubkey, record: &GameRecord, lamports: u64) {
+        let mut data = vec![0; GameRecord::LEN];
+        record.pack_into_slice(&mut data);
+
+        let account = Account {
+            lamports,
+            data,
+            owner: digital_sol_game::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(key, &account.into());
+    }
+
+    pub async fn record(&mut self, key: &Pubkey) -> GameRecord {
+        let account = self.context.banks_client.get_account(*key).await.unwrap().unwrap();
+        GameRecord::unpack_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn create(
+        &mut self,
+        game: &TestGame,
+        odds_x100: u32,
+        level: GameLevel,
+    ) -> Result<(), BanksClientError> {
+        self.create_with_reveal_time(game, odds_x100, level, 0).await
+    }
+
+    /// A `LEVEL` game at `ODDS_X100` answered `answer`, joined by the player at `point`.
+    pub async fn joined(&mut self, answer: u16, point: u16) -> TestGame {
+        let game = self.game(10, answer);
+        self.create(&game, ODDS_X100, LEVEL).await.unwrap();
+        self.participate(&game, point).await.unwrap();
+        game
+    }
+
+    pub async fn create_with_reveal_time(
+        &mut self,
+        game: &TestGame,
+        odds_x100: u32,
+        level: GameLevel,
+        reveal_time: i64,
+    ) -> Result<(), BanksClientError> {
+        let mut params = Self::create_params(game, odds_x100, level);
+        params.reveal_time = reveal_time;
+        self.create_with(params).await
+    }
+
+    pub fn create_params(game: &TestGame, odds_x100: u32, level: GameLevel) -> create_game::Params {
+        create_game::Params {
+            max_number: game.max,
+            odds_x100,
+            splicing_hash: game.hash,
+            game_level: level as u8,
+            reveal_time: 0,
+            open_until: 0,
+        }
+    }
+
+    pub async fn create_with(&mut self, params: create_game::Params) -> Result<(), BanksClientError> {
+        let instruction = instruction::create_game(&self.promoter.pubkey(), params);
+        let promoter = self.promoter.insecure_clone();
+        self.send(instruction, &[&promoter]).await
+    }
+
+    pub async fn participate_as(
+        &mut self,
+        player: &Keypair,
+        game: &TestGame,
+        point: u16,
+    ) -> Result<(), BanksClientError> {
+        let instruction = self.participate_ix(&player.pubkey(), game, point);
+        self.send(instruction, &[player]).await
+    }
+
+    pub fn participate_ix(&self, player: &Pubkey, game: &TestGame, point: u16) -> Instruction {
+        instruction::participate(player, &self.promoter.pubkey(), &game.key, point)
+    }
+
+    pub async fn participate(&mut self, game: &TestGame, point: u16) -> Result<(), BanksClientError> {
+        let player = self.player.insecure_clone();
+        self.participate_as(&player, game, point).await
+    }
+
+    pub async fn reveal(&mut self, game: &TestGame) -> Result<(), BanksClientError> {
+        self.reveal_with(game, game.answer, game.secret.clone()).await
+    }
+
+    pub async fn reveal_with(
+        &mut self,
+        game: &TestGame,
+        x: u16,
+        secret: Secret,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction::reveal(&self.promoter.pubkey(), &game.key, reveal::Params { x, secret });
+        let promoter = self.promoter.insecure_clone();
+        self.send(instruction, &[&promoter]).await
+    }
+
+    pub async fn end_with(
+        &mut self,
+        terminator: &Keypair,
+        player: &Pubkey,
+        game: &TestGame,
+    ) -> Result<(), BanksClientError> {
+        let instruction = instruction::end(&terminator.pubkey(), &self.promoter.pubkey(), player, &game.key);
+        self.send(instruction, &[terminator]).await
+    }
+
+    pub async fn end(&mut self, game: &TestGame) -> Result<(), BanksClientError> {
+        let player = self.player.insecure_clone();
+        let player_key = player.pubkey();
+        self.end_with(&player, &player_key, game).await
+    }
+
+    pub async fn delete(&mut self, game: &TestGame) -> Result<(), BanksClientError> {
+        let instruction = self.delete_ix(game);
+        let promoter = self.promoter.insecure_clone();
+        self.send(instruction, &[&promoter]).await
+    }
+
+    pub fn delete_ix(&self, game: &TestGame) -> Instruction {
+        instruction::delete(&self.promoter.pubkey(), &game.key)
+    }
+
+    /// Reclaims an expired game, signed only by the fee payer.
+    pub async fn reclaim(&mut self, game: &TestGame) -> Result<(), BanksClientError> {
+        let instruction = instruction::reclaim(&self.promoter.pubkey(), &game.key);
+        self.send(instruction, &[]).await
+    }
+}
+
+pub fn assert_game_error(result: Result<(), BanksClientError>, expected: GameError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(GameError::from_code(code), Some(expected)),
+        other => panic!("expected {:?}, got {:?}", expected, other),
+    }
+}
+
+/// How `LogDataStubs` writes `sol_log_data` fields to the transaction logs.
+const LOG_DATA_PREFIX: &str = "Program log: data: ";
+
+/// program-test's syscall stubs print `sol_log_data` to stdout, this wraps
+/// them to write the fields to the transaction logs instead, as a
+/// `LOG_DATA_PREFIX` line `send_for_logs` turns back into `Program data: `.
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+struct NoStubs;
+
+impl SyscallStubs for NoStubs {}
+
+/// Wraps program-test's stubs, which are set when the first bank starts.
+fn install_log_data_stubs() {
+    static ONCE: Once = Once::new();
+
+    ONCE.call_once(|| {
+        let stubs = set_syscall_stubs(Box::new(NoStubs));
+        set_syscall_stubs(Box::new(LogDataStubs(stubs)));
+    });
+}
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0.sol_log(&format!("data: {}", fields.join(" ")))
+    }
+
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0.sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_sysvar(&self, sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64 {
+        self.0.sol_get_sysvar(sysvar_id_addr, var_addr, offset, length)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+
+    fn sol_get_epoch_stake(&self, vote_address: *const u8) -> u64 {
+        self.0.sol_get_epoch_stake(vote_address)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::{amounts, assert_game_error, config_params, start, start_without_config, FEE_BPS, LEVEL, MAX_REVEAL_TIME, MIN_REVEAL_TIME, ODDS_X100, REVEAL_TIME};
+use digital_sol_game::{
+    error::GameError,
+    state::{config::Config, game::GameLevel},
+    utils::get_vault_key,
+};
+use solana_sdk::{
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn init_config_stores_the_settings() {
+    let mut env = start().await;
+
+    let config = env.config().await;
+    assert_eq!(config.admin, env.admin.pubkey());
+    assert_eq!(config.vault, get_vault_key().0);
+    assert_eq!(config.fee_bps, FEE_BPS);
+    assert_eq!(config.reveal_time, REVEAL_TIME);
+    assert_eq!(config.min_reveal_time, MIN_REVEAL_TIME);
+    assert_eq!(config.max_reveal_time, MAX_REVEAL_TIME);
+    assert_eq!(config.bet(LEVEL), LEVEL.get_bet());
+    assert!(config.is_level_enabled(GameLevel::S));
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_can_init_the_config() {
+    let mut env = start_without_config().await;
+    let params = config_params();
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.init_config_as(&promoter, params).await;
+    assert_game_error(result, GameError::Unauthorized);
+}
+
+#[tokio::test]
+async fn invalid_settings_are_rejected() {
+    let mut env = start_without_config().await;
+
+    let mut params = config_params();
+    params.fee_bps = 10_001;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.reveal_time = 0;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.crank_reward_bps = 10_001;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.settle_grace_time = -1;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.min_reveal_time = 0;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.max_reveal_time = params.reveal_time - 1;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+
+    let mut params = config_params();
+    params.bets[GameLevel::B as usize] = 0;
+    assert_game_error(env.init_config(params).await, GameError::InvalidConfig);
+}
+
+#[tokio::test]
+async fn only_the_admin_can_update_the_config() {
+    let mut env = start().await;
+
+    let mut params = config_params();
+    params.fee_bps = 250;
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.update_config_as(&promoter, params.clone()).await;
+    assert_game_error(result, GameError::Unauthorized);
+
+    env.update_config(params).await.unwrap();
+    assert_eq!(env.config().await.fee_bps, 250);
+}
+
+#[tokio::test]
+async fn transferred_admin_takes_over() {
+    let mut env = start().await;
+    let new_admin = Keypair::new();
+
+    let admin = env.admin.insecure_clone();
+    env.transfer_admin_as(&admin, new_admin.pubkey()).await.unwrap();
+    assert_eq!(env.config().await.admin, new_admin.pubkey());
+
+    let params = config_params();
+    let result = env.update_config(params.clone()).await;
+    assert_game_error(result, GameError::Unauthorized);
+
+    let result = env.transfer_admin_as(&admin, admin.pubkey()).await;
+    assert_game_error(result, GameError::Unauthorized);
+
+    env.update_config_as(&new_admin, params).await.unwrap();
+}
+
+#[tokio::test]
+async fn disabled_levels_cannot_be_created() {
+    let mut env = start().await;
+
+    let mut params = config_params();
+    params.enabled_levels = 0b01111;
+    env.update_config(params).await.unwrap();
+
+    let game = env.game(10, 3);
+    let result = env.create(&game, ODDS_X100, LEVEL).await;
+    assert_game_error(result, GameError::LevelDisabled);
+
+    env.create(&game, ODDS_X100, GameLevel::C).await.unwrap();
+}
+
+#[tokio::test]
+async fn settlement_uses_the_configured_fee() {
+    let mut env = start().await;
+
+    let mut params = config_params();
+    params.fee_bps = 500;
+    env.update_config(params).await.unwrap();
+
+    let game = env.joined(3, 5).await;
+    env.reveal(&game).await.unwrap();
+
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+    env.end(&game).await.unwrap();
+
+    assert_eq!(env.lamports(&vault).await, vault_before + amounts().win * 500 / 10_000);
+}
+
+#[tokio::test]
+async fn bet_is_fixed_when_the_game_is_created() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    let mut params = config_params();
+    params.bets[LEVEL as usize] *= 10;
+    env.update_config(params).await.unwrap();
+
+    let player_key = env.player.pubkey();
+    let player_before = env.lamports(&player_key).await;
+    env.participate(&game, 5).await.unwrap();
+
+    assert_eq!(env.lamports(&player_key).await, player_before - LEVEL.get_bet());
+    assert_eq!(env.record(&game.key).await.stake(), LEVEL.get_bet());
+}
+
+#[test]
+fn short_destination_is_left_untouched() {
+    let config = config_params().into_config(Pubkey::new_unique());
+    let mut data = vec![0xaa; Config::LEN - 1];
+
+    assert_eq!(config.try_pack_into_slice(&mut data), Err(ProgramError::AccountDataTooSmall));
+    assert!(data.iter().all(|b| *b == 0xaa));
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{amounts, assert_game_error, start, system_account, Env, SETTLE_GRACE_TIME, START_LAMPORTS};
+use digital_sol_game::error::GameError;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A funded keeper that is neither the player nor the promoter.
+fn keeper(env: &mut Env) -> Keypair {
+    let keeper = Keypair::new();
+    env.context.set_account(&keeper.pubkey(), &system_account(START_LAMPORTS).into());
+    keeper
+}
+
+#[tokio::test]
+async fn anyone_settles_an_unrevealed_game_after_the_deadline() {
+    let mut env = start().await;
+    let keeper = keeper(&mut env);
+    let player = env.player.pubkey();
+    let a = amounts();
+    let game = env.joined(3, 5).await;
+    let deadline = env.record(&game.key).await.reveal_deadline;
+
+    env.set_time(deadline).await;
+    assert_game_error(env.end_with(&keeper, &player, &game).await, GameError::RevealPending);
+
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+
+    env.set_time(deadline + 1).await;
+    env.end_with(&keeper, &player, &game).await.unwrap();
+
+    assert_eq!(env.lamports(&keeper.pubkey()).await, START_LAMPORTS + a.reward);
+    assert_eq!(env.lamports(&vault).await, vault_before + a.fee - a.reward);
+    assert_eq!(env.vault_state().await.fees_collected, a.fee - a.reward);
+    // (over time, not revealed): the player takes the escrow minus the fee
+    assert_eq!(env.lamports(&player).await, START_LAMPORTS + a.win);
+    assert_eq!(env.lamports(&game.key).await, 0);
+}
+
+#[tokio::test]
+async fn revealed_game_is_reserved_during_the_grace_time() {
+    let mut env = start().await;
+    let keeper = keeper(&mut env);
+    let player = env.player.pubkey();
+    let a = amounts();
+    let game = env.joined(3, 5).await;
+
+    env.reveal(&game).await.unwrap();
+    let revealed_at = env.record(&game.key).await.revealed_at;
+    assert_ne!(revealed_at, 0);
+
+    env.set_time(revealed_at + SETTLE_GRACE_TIME).await;
+    assert_game_error(env.end_with(&keeper, &player, &game).await, GameError::InvalidSettler);
+
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+
+    env.set_time(revealed_at + SETTLE_GRACE_TIME + 1).await;
+    env.end_with(&keeper, &player, &game).await.unwrap();
+
+    assert_eq!(env.lamports(&keeper.pubkey()).await, START_LAMPORTS + a.reward);
+    assert_eq!(env.lamports(&vault).await, vault_before + a.fee - a.reward);
+}
+
+#[tokio::test]
+async fn player_and_promoter_earn_no_reward() {
+    let mut env = start().await;
+    let a = amounts();
+    let game = env.joined(3, 5).await;
+    let deadline = env.record(&game.key).await.reveal_deadline;
+
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+
+    env.set_time(deadline + 1).await;
+    env.end(&game).await.unwrap();
+
+    assert_eq!(env.lamports(&vault).await, vault_before + a.fee);
+    assert_eq!(env.vault_state().await.fees_collected, a.fee);
+}
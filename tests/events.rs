@@ -0,0 +1,133 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshSerialize;
+use common::{amounts, start, Amounts, Env, LEVEL, ODDS_X100};
+use digital_sol_game::{
+    events::{decode_logs, GameCreated, GameDeleted, GameEvent, GameJoined, GameRevealed, GameSettled, EVENT_TAG},
+    instruction,
+    processor::reveal,
+    settlement::Outcome,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+fn data_log(fields: &[&[u8]]) -> String {
+    let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+    format!("Program data: {}", fields.join(" "))
+}
+
+fn revealed() -> GameEvent {
+    GameEvent::GameRevealed(GameRevealed { game: Pubkey::new_unique(), answer: 3, forfeited: false })
+}
+
+#[test]
+fn event_logs_round_trip() {
+    let event = revealed();
+    let line = data_log(&[EVENT_TAG, &event.try_to_vec().unwrap()]);
+
+    assert_eq!(GameEvent::decode_log(&line), Some(event));
+}
+
+#[test]
+fn other_log_lines_are_skipped() {
+    let event = revealed();
+    let data = event.try_to_vec().unwrap();
+
+    let logs = [
+        "Program log: player win".to_string(),
+        data_log(&[&data]),
+        data_log(&[b"other program", &data]),
+        data_log(&[EVENT_TAG, &data, &data]),
+        data_log(&[EVENT_TAG, &data[..data.len() - 1]]),
+        format!("Program data: {} not-base64", STANDARD.encode(EVENT_TAG)),
+        data_log(&[EVENT_TAG, &data]),
+    ];
+
+    assert_eq!(decode_logs(&logs), vec![event]);
+}
+
+#[tokio::test]
+async fn game_lifecycle_is_logged() {
+    let mut env = start().await;
+    let (promoter, player) = (env.promoter.insecure_clone(), env.player.insecure_clone());
+    let game = env.game(10, 3);
+
+    let Amounts { bet, win, fee, rent, .. } = amounts();
+
+    let ix = instruction::create_game(&promoter.pubkey(), Env::create_params(&game, ODDS_X100, LEVEL));
+    let events = env.send_for_events(ix, &[&promoter]).await;
+    let record = env.record(&game.key).await;
+    assert_eq!(events, vec![GameEvent::GameCreated(GameCreated {
+        game: game.key,
+        promoter: promoter.pubkey(),
+        max: game.max,
+        odds_x100: ODDS_X100,
+        level: LEVEL as u8,
+        bet,
+        collateral: win + fee + rent,
+        reveal_time: record.reveal_time,
+        open_until: 0,
+    })]);
+
+    let ix = env.participate_ix(&player.pubkey(), &game, 3);
+    let events = env.send_for_events(ix, &[&player]).await;
+    let record = env.record(&game.key).await;
+    assert_eq!(events, vec![GameEvent::GameJoined(GameJoined {
+        game: game.key,
+        player: player.pubkey(),
+        point: 3,
+        stake: bet,
+        reveal_deadline: record.reveal_deadline,
+    })]);
+
+    let params = reveal::Params { x: game.answer, secret: game.secret.clone() };
+    let ix = instruction::reveal(&promoter.pubkey(), &game.key, params);
+    let events = env.send_for_events(ix, &[&promoter]).await;
+    assert_eq!(events, vec![GameEvent::GameRevealed(GameRevealed { game: game.key, answer: 3, forfeited: false })]);
+
+    let ix = instruction::end(&player.pubkey(), &promoter.pubkey(), &player.pubkey(), &game.key);
+    let events = env.send_for_events(ix, &[&player]).await;
+    assert_eq!(events, vec![GameEvent::GameSettled(GameSettled {
+        game: game.key,
+        settler: player.pubkey(),
+        outcome: Outcome::PlayerWin,
+        to_player: win - fee,
+        to_promoter: fee + bet,
+        to_vault: fee,
+        crank_reward: 0,
+        rent,
+    })]);
+}
+
+#[tokio::test]
+async fn deleted_game_is_logged() {
+    let mut env = start().await;
+    let promoter = env.promoter.insecure_clone();
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    let refund = env.lamports(&game.key).await;
+
+    let events = env.send_for_events(env.delete_ix(&game), &[&promoter]).await;
+    assert_eq!(events, vec![GameEvent::GameDeleted(GameDeleted {
+        game: game.key,
+        promoter: promoter.pubkey(),
+        refund,
+        expired: false,
+    })]);
+}
+
+#[tokio::test]
+async fn reclaimed_game_is_logged_as_expired() {
+    let mut env = start().await;
+    let promoter = env.promoter.pubkey();
+    let game = env.game(10, 3);
+
+    let mut params = Env::create_params(&game, ODDS_X100, LEVEL);
+    params.open_until = env.now().await + 60;
+    env.create_with(params.clone()).await.unwrap();
+    let refund = env.lamports(&game.key).await;
+
+    env.set_time(params.open_until + 1).await;
+    let events = env.send_for_events(instruction::reclaim(&promoter, &game.key), &[]).await;
+    assert_eq!(events, vec![GameEvent::GameDeleted(GameDeleted { game: game.key, promoter, refund, expired: true })]);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::{assert_game_error, start, Env, TestGame, LEVEL, ODDS_X100, START_LAMPORTS};
+use digital_sol_game::{error::GameError, instruction};
+use solana_sdk::signature::Signer;
+
+const OPEN_TIME: i64 = 3600;
+
+/// An open game joinable until the returned timestamp.
+async fn open(env: &mut Env) -> (TestGame, i64) {
+    let game = env.game(10, 3);
+    let open_until = env.now().await + OPEN_TIME;
+
+    let mut params = Env::create_params(&game, ODDS_X100, LEVEL);
+    params.open_until = open_until;
+    env.create_with(params).await.unwrap();
+    assert_eq!(env.record(&game.key).await.open_until, open_until);
+
+    (game, open_until)
+}
+
+#[tokio::test]
+async fn game_is_joinable_until_it_expires() {
+    let mut env = start().await;
+    let (game, open_until) = open(&mut env).await;
+
+    env.set_time(open_until + 1).await;
+    assert_game_error(env.participate(&game, 3).await, GameError::GameExpired);
+
+    let mut env = start().await;
+    let (game, open_until) = open(&mut env).await;
+
+    env.set_time(open_until).await;
+    env.participate(&game, 3).await.unwrap();
+}
+
+#[tokio::test]
+async fn anyone_reclaims_an_expired_game_for_the_promoter() {
+    let mut env = start().await;
+    let promoter = env.promoter.pubkey();
+    let (game, open_until) = open(&mut env).await;
+    assert!(env.lamports(&promoter).await < START_LAMPORTS);
+
+    env.set_time(open_until).await;
+    assert_game_error(env.reclaim(&game).await, GameError::GameNotExpired);
+
+    env.set_time(open_until + 1).await;
+    env.reclaim(&game).await.unwrap();
+
+    assert_eq!(env.lamports(&promoter).await, START_LAMPORTS);
+    assert_eq!(env.lamports(&game.key).await, 0);
+    assert_game_error(env.participate(&game, 3).await, GameError::GameClosed);
+}
+
+#[tokio::test]
+async fn only_expired_open_games_are_reclaimed() {
+    let mut env = start().await;
+
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    env.warp(OPEN_TIME * 100).await;
+    assert_game_error(env.reclaim(&game).await, GameError::GameNotExpired);
+
+    let (game, open_until) = open(&mut env).await;
+    env.participate(&game, 3).await.unwrap();
+    env.set_time(open_until + 1).await;
+    assert_game_error(env.reclaim(&game).await, GameError::GameAlreadyStarted);
+
+    let (game, open_until) = open(&mut env).await;
+    env.set_time(open_until + 1).await;
+    let ix = instruction::reclaim(&env.player.pubkey(), &game.key);
+    assert_game_error(env.send(ix, &[]).await, GameError::PromoterMismatch);
+}
+
+#[tokio::test]
+async fn game_cant_be_created_already_expired() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    let mut params = Env::create_params(&game, ODDS_X100, LEVEL);
+    params.open_until = env.now().await - 1;
+    assert_game_error(env.create_with(params).await, GameError::GameExpired);
+}
@@ -0,0 +1,162 @@
+use digital_sol_game::state::{
+    game::{CommitmentScheme, GameLevel, GameRecord, GameStatus},
+    header::AccountHeader,
+};
+use proptest::prelude::*;
+use solana_sdk::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+/// Offset of the level byte in a v2 record.
+const LEVEL_OFFSET: usize = AccountHeader::LEN + 32 + 32 + 2 + 2 + 4;
+
+fn level() -> impl Strategy<Value = GameLevel> {
+    prop_oneof![
+        Just(GameLevel::S),
+        Just(GameLevel::A),
+        Just(GameLevel::B),
+        Just(GameLevel::C),
+        Just(GameLevel::D),
+    ]
+}
+
+fn status() -> impl Strategy<Value = GameStatus> {
+    prop_oneof![
+        Just(GameStatus::Open),
+        Just(GameStatus::Joined),
+        Just(GameStatus::Revealed),
+        Just(GameStatus::Settled),
+        Just(GameStatus::Cancelled),
+        Just(GameStatus::Forfeited),
+    ]
+}
+
+fn commitment() -> impl Strategy<Value = CommitmentScheme> {
+    prop_oneof![Just(CommitmentScheme::Legacy), Just(CommitmentScheme::Salted)]
+}
+
+prop_compose! {
+    fn record()(
+        promoter in any::<[u8; 32]>(),
+        player in any::<[u8; 32]>(),
+        firing_point in any::<u16>(),
+        max in any::<u16>(),
+        odds_x100 in any::<u32>(),
+        level in level(),
+        shot_time in any::<i64>(),
+        answer in any::<u16>(),
+        random_string in any::<[u8; 6]>(),
+        status in status(),
+        commitment in commitment(),
+        bet in any::<u64>(),
+        reveal_time in any::<i64>(),
+        reveal_deadline in any::<i64>(),
+        revealed_at in any::<i64>(),
+        open_until in any::<i64>(),
+        bump in any::<u8>(),
+        fee_bps in any::<u16>(),
+    ) -> GameRecord {
+        GameRecord {
+            promoter: Pubkey::new_from_array(promoter),
+            player: Pubkey::new_from_array(player),
+            firing_point,
+            max,
+            odds_x100,
+            level,
+            shot_time,
+            answer,
+            random_string,
+            status,
+            commitment,
+            bet,
+            reveal_time,
+            reveal_deadline,
+            revealed_at,
+            open_until,
+            bump,
+            fee_bps,
+        }
+    }
+}
+
+fn packed(record: &GameRecord) -> Vec<u8> {
+    let mut data = vec![0xaa; GameRecord::LEN];
+    record.try_pack_into_slice(&mut data).unwrap();
+    data
+}
+
+proptest! {
+    #[test]
+    fn round_trip(record in record()) {
+        let data = packed(&record);
+
+        prop_assert_eq!(GameRecord::unpack_from_slice(&data)?, record.clone());
+        prop_assert_eq!(GameRecord::unpack_unchecked(&data)?, record);
+    }
+
+    #[test]
+    fn reserved_tail_is_zeroed(record in record()) {
+        let data = packed(&record);
+        let tail = AccountHeader::LEN + GameRecord::V1_LEN + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 2;
+
+        prop_assert!(data[tail..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn short_destination_is_left_untouched(record in record(), len in 0..GameRecord::LEN, fill in any::<u8>()) {
+        let mut data = vec![fill; len];
+
+        prop_assert_eq!(record.try_pack_into_slice(&mut data), Err(ProgramError::AccountDataTooSmall));
+        prop_assert!(data.iter().all(|b| *b == fill));
+        prop_assert!(GameRecord::pack(record, &mut data).is_err());
+    }
+
+    #[test]
+    fn truncated_source_is_rejected(record in record(), len in 0..GameRecord::LEN) {
+        let data = packed(&record);
+
+        prop_assert!(GameRecord::unpack_from_slice(&data[..len]).is_err());
+        prop_assert!(GameRecord::unpack_unchecked(&data[..len]).is_err());
+    }
+
+    #[test]
+    fn oversized_source_only_reads_the_record(record in record(), extra in prop::collection::vec(any::<u8>(), 1..64)) {
+        let mut data = packed(&record);
+        data.extend_from_slice(&extra);
+
+        prop_assert_eq!(GameRecord::unpack_from_slice(&data)?, record);
+        prop_assert!(GameRecord::unpack_unchecked(&data).is_err());
+    }
+
+    #[test]
+    fn invalid_level_is_rejected(record in record(), level in 5u8..) {
+        let mut data = packed(&record);
+        data[LEVEL_OFFSET] = level;
+
+        prop_assert_eq!(GameRecord::unpack_from_slice(&data), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn garbage_never_panics(data in prop::collection::vec(any::<u8>(), 0..2 * GameRecord::LEN)) {
+        if let Ok(record) = GameRecord::unpack_from_slice(&data) {
+            prop_assert_eq!(GameRecord::unpack_from_slice(&packed(&record))?, record);
+        }
+        let _ = GameRecord::unpack_v1(&data);
+    }
+
+    #[test]
+    fn garbage_with_a_valid_header_never_panics(body in prop::collection::vec(any::<u8>(), GameRecord::LEN - AccountHeader::LEN)) {
+        let valid = packed(&GameRecord::new(Pubkey::new_unique(), 10, 200, GameLevel::D, 0));
+        let mut data = valid[..AccountHeader::LEN].to_vec();
+        data.extend_from_slice(&body);
+
+        if let Ok(record) = GameRecord::unpack_from_slice(&data) {
+            prop_assert_eq!(GameRecord::unpack_from_slice(&packed(&record))?, record);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "destination slice too small")]
+fn pack_into_slice_no_longer_fails_silently() {
+    let record = GameRecord::new(Pubkey::new_unique(), 10, 200, GameLevel::D, 0);
+    record.pack_into_slice(&mut [0; GameRecord::LEN - 1]);
+}
@@ -0,0 +1,294 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::start;
+use digital_sol_game::{
+    constants::{CONFIG_SEED, GAME_SEED, SYSTEM_ID, VAULT_SEED},
+    instruction::{self, get_game_key, legacy, ProgramInstruction, INSTRUCTION_VERSION, VERSION_PREFIX},
+    processor::{
+        create_game, init_config, participate_game, reveal, set_withdraw_authority,
+        transfer_admin, withdraw_fees,
+    },
+    utils::{get_config_key, get_program_data_key, get_vault_key},
+};
+use solana_sdk::{
+    hash::hashv,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
+use solana_program_test::BanksClientError;
+use web3_utils::accounts::InstructionsAccount;
+
+fn assert_metas(instruction: &Instruction, expected_data: ProgramInstruction, expected: &[AccountMeta]) {
+    assert_eq!(instruction.program_id, digital_sol_game::ID);
+    assert_eq!(ProgramInstruction::unpack(&instruction.data), Ok(expected_data));
+    assert_eq!(instruction.accounts, expected);
+}
+
+fn create_params() -> create_game::Params {
+    create_game::Params { max_number: 10, odds_x100: 200, splicing_hash: [7; 32], game_level: 4, reveal_time: 0, open_until: 0 }
+}
+
+fn init_params() -> init_config::Params {
+    init_config::Params {
+        fee_bps: 0,
+        reveal_time: 1,
+        min_reveal_time: 1,
+        max_reveal_time: 1,
+        crank_reward_bps: 0,
+        settle_grace_time: 0,
+        enabled_levels: 1,
+        bets: [1; 5],
+    }
+}
+
+fn every_instruction() -> Vec<ProgramInstruction> {
+    vec![
+        ProgramInstruction::CreateGame(create_params()),
+        ProgramInstruction::Participate(participate_game::Params { point: 3 }),
+        ProgramInstruction::Reveal(reveal::Params { x: 3, secret: reveal::Secret::Legacy { random: [1; 6] } }),
+        ProgramInstruction::Reveal(reveal::Params { x: 3, secret: reveal::Secret::Salted { salt: [1; 32] } }),
+        ProgramInstruction::End,
+        ProgramInstruction::Delete,
+        ProgramInstruction::Migrate,
+        ProgramInstruction::InitConfig(init_params()),
+        ProgramInstruction::UpdateConfig(init_params()),
+        ProgramInstruction::TransferAdmin(transfer_admin::Params { new_admin: Pubkey::new_unique() }),
+        ProgramInstruction::WithdrawFees(withdraw_fees::Params { amount: u64::MAX }),
+        ProgramInstruction::SetWithdrawAuthority(set_withdraw_authority::Params {
+            threshold: 2,
+            signers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        }),
+        ProgramInstruction::Reclaim,
+    ]
+}
+
+#[test]
+fn pack_round_trips() {
+    for instruction in every_instruction() {
+        let data = instruction.pack();
+        assert_eq!(data[..2], [VERSION_PREFIX, INSTRUCTION_VERSION]);
+        assert_eq!(ProgramInstruction::unpack(&data), Ok(instruction));
+    }
+}
+
+fn legacy_create_params() -> legacy::CreateGameParams {
+    legacy::CreateGameParams { max_number: 10, odds_x100: 200, splicing_hash: [7; 32], game_level: 4 }
+}
+
+#[test]
+fn unversioned_data_is_still_accepted() {
+    let defaults = [
+        (legacy::ProgramInstruction::CreateGame(legacy_create_params()), ProgramInstruction::CreateGame(create_params())),
+        (
+            legacy::ProgramInstruction::Participate(participate_game::Params { point: 3 }),
+            ProgramInstruction::Participate(participate_game::Params { point: 3 }),
+        ),
+        (
+            legacy::ProgramInstruction::Reveal(legacy::RevealParams { x: 3, random: [1; 6] }),
+            ProgramInstruction::Reveal(reveal::Params { x: 3, secret: reveal::Secret::Legacy { random: [1; 6] } }),
+        ),
+        (legacy::ProgramInstruction::End, ProgramInstruction::End),
+        (legacy::ProgramInstruction::Delete, ProgramInstruction::Delete),
+    ];
+
+    for (legacy, current) in defaults {
+        let data = legacy.try_to_vec().unwrap();
+        assert_eq!(ProgramInstruction::unpack(&data), Ok(current));
+    }
+
+    // what `InstructionsAccount::get_instruction` used to send
+    let original = create_game::Accounts {
+        promoter: &Pubkey::new_unique(),
+        game_recorder: &Pubkey::new_unique(),
+        system_program: &SYSTEM_ID,
+        config: &get_config_key().0,
+    }
+    .get_instruction(digital_sol_game::ID, 0, legacy_create_params());
+    assert_eq!(
+        ProgramInstruction::unpack(&original.data),
+        Ok(ProgramInstruction::CreateGame(create_params())),
+    );
+}
+
+#[test]
+fn unversioned_data_needs_the_original_layout() {
+    let invalid = Err(ProgramError::InvalidInstructionData);
+
+    // current layouts that changed since, and the instructions it didn't have
+    for instruction in every_instruction() {
+        if matches!(
+            instruction,
+            ProgramInstruction::Participate(_) | ProgramInstruction::End | ProgramInstruction::Delete
+        ) {
+            continue;
+        }
+        let data = instruction.try_to_vec().unwrap();
+        assert_eq!(ProgramInstruction::unpack(&data), invalid, "{:?}", instruction);
+    }
+}
+
+#[test]
+fn version_1_is_rejected() {
+    // never deployed, the config updates it carried would drop the reveal
+    // time bounds and the crank settings
+    for instruction in every_instruction() {
+        let mut data = instruction.pack();
+        data[1] = 1;
+        assert_eq!(ProgramInstruction::unpack(&data), Err(ProgramError::InvalidInstructionData), "{:?}", instruction);
+    }
+}
+
+#[test]
+fn malformed_data_is_rejected() {
+    let invalid = Err(ProgramError::InvalidInstructionData);
+
+    for instruction in every_instruction() {
+        let mut data = instruction.pack();
+        data.push(0);
+        assert_eq!(ProgramInstruction::unpack(&data), invalid, "trailing byte {:?}", instruction);
+
+        let data = instruction.pack();
+        if data.len() > 3 {
+            assert_eq!(ProgramInstruction::unpack(&data[..data.len() - 1]), invalid, "truncated {:?}", instruction);
+        }
+    }
+
+    assert_eq!(ProgramInstruction::unpack(&[]), invalid);
+    assert_eq!(ProgramInstruction::unpack(&[VERSION_PREFIX]), invalid);
+    assert_eq!(ProgramInstruction::unpack(&[VERSION_PREFIX, INSTRUCTION_VERSION]), invalid);
+    assert_eq!(ProgramInstruction::unpack(&[VERSION_PREFIX, INSTRUCTION_VERSION + 1, 3]), invalid);
+    assert_eq!(ProgramInstruction::unpack(&[12]), invalid);
+    assert_eq!(ProgramInstruction::unpack(&[VERSION_PREFIX, INSTRUCTION_VERSION, 12]), invalid);
+}
+
+#[tokio::test]
+async fn empty_instruction_data_fails_cleanly() {
+    let mut env = start().await;
+    let instruction = Instruction { program_id: digital_sol_game::ID, accounts: vec![], data: vec![] };
+
+    match env.send(instruction, &[]).await {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidInstructionData,
+        ))) => {}
+        other => panic!("expected InvalidInstructionData, got {:?}", other),
+    }
+}
+
+#[test]
+fn precomputed_keys_match_their_seeds() {
+    assert_eq!(get_config_key(), Pubkey::find_program_address(&[CONFIG_SEED], &digital_sol_game::ID));
+    assert_eq!(get_vault_key(), Pubkey::find_program_address(&[VAULT_SEED], &digital_sol_game::ID));
+    assert_eq!(GAME_SEED, hashv(&[b"game"]).to_bytes());
+}
+
+#[test]
+fn game_instructions() {
+    let promoter = Pubkey::new_unique();
+    let player = Pubkey::new_unique();
+    let hash = create_params().splicing_hash;
+    let game = get_game_key(&hash);
+    let config = get_config_key().0;
+    let vault = get_vault_key().0;
+    assert_eq!(game, Pubkey::find_program_address(&[&hash, &GAME_SEED], &digital_sol_game::ID).0);
+
+    let create = instruction::create_game(&promoter, create_params());
+    assert_metas(&create, ProgramInstruction::CreateGame(create_params()), &[
+        AccountMeta::new(promoter, true),
+        AccountMeta::new(game, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+        AccountMeta::new_readonly(config, false),
+    ]);
+
+    let participate = instruction::participate(&player, &promoter, &game, 3);
+    assert_metas(&participate, ProgramInstruction::Participate(participate_game::Params { point: 3 }), &[
+        AccountMeta::new(player, true),
+        AccountMeta::new(game, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+        AccountMeta::new_readonly(promoter, false),
+        AccountMeta::new_readonly(config, false),
+    ]);
+
+    let params = reveal::Params { x: 3, secret: reveal::Secret::Salted { salt: [1; 32] } };
+    let reveal = instruction::reveal(&promoter, &game, params.clone());
+    assert_metas(&reveal, ProgramInstruction::Reveal(params), &[
+        AccountMeta::new(promoter, true),
+        AccountMeta::new(game, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+        AccountMeta::new_readonly(config, false),
+    ]);
+
+    let end = instruction::end(&player, &promoter, &player, &game);
+    assert_metas(&end, ProgramInstruction::End, &[
+        AccountMeta::new(player, true),
+        AccountMeta::new(promoter, false),
+        AccountMeta::new(player, false),
+        AccountMeta::new(game, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(config, false),
+    ]);
+
+    let delete = instruction::delete(&promoter, &game);
+    assert_metas(&delete, ProgramInstruction::Delete, &[
+        AccountMeta::new(promoter, true),
+        AccountMeta::new(game, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+    ]);
+
+    let migrate = instruction::migrate(&player, &game);
+    assert_metas(&migrate, ProgramInstruction::Migrate, &[
+        AccountMeta::new(player, true),
+        AccountMeta::new(game, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+    ]);
+
+    let reclaim = instruction::reclaim(&promoter, &game);
+    assert_metas(&reclaim, ProgramInstruction::Reclaim, &[
+        AccountMeta::new(promoter, false),
+        AccountMeta::new(game, false),
+    ]);
+}
+
+#[test]
+fn admin_instructions() {
+    let admin = Pubkey::new_unique();
+    let config = get_config_key().0;
+    let vault = get_vault_key().0;
+
+    let new_admin = Pubkey::new_unique();
+    let transfer = instruction::transfer_admin(&admin, &new_admin);
+    assert_metas(&transfer, ProgramInstruction::TransferAdmin(transfer_admin::Params { new_admin }), &[
+        AccountMeta::new_readonly(admin, true),
+        AccountMeta::new(config, false),
+    ]);
+
+    let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let destination = Pubkey::new_unique();
+    let withdraw = instruction::withdraw_fees(&destination, &members, 10);
+    assert_metas(&withdraw, ProgramInstruction::WithdrawFees(withdraw_fees::Params { amount: 10 }), &[
+        AccountMeta::new(vault, false),
+        AccountMeta::new(destination, false),
+        AccountMeta::new_readonly(members[0], true),
+        AccountMeta::new_readonly(members[1], true),
+    ]);
+
+    let params = set_withdraw_authority::Params { threshold: 1, signers: vec![new_admin] };
+    let set_authority = instruction::set_withdraw_authority(&members, params.clone());
+    assert_metas(&set_authority, ProgramInstruction::SetWithdrawAuthority(params), &[
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(members[0], true),
+        AccountMeta::new_readonly(members[1], true),
+    ]);
+
+    let init = instruction::init_config(&admin, init_params());
+    assert_metas(&init, ProgramInstruction::InitConfig(init_params()), &[
+        AccountMeta::new(admin, true),
+        AccountMeta::new(config, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new_readonly(get_program_data_key().0, false),
+        AccountMeta::new_readonly(SYSTEM_ID, false),
+    ]);
+}
@@ -0,0 +1,172 @@
+mod common;
+
+use common::{
+    amounts, assert_game_error, start, system_account, Env, TestGame, LEVEL, ODDS_X100, REVEAL_TIME, START_LAMPORTS,
+};
+use digital_sol_game::{error::GameError, instruction, processor::reveal};
+use solana_sdk::signature::{Keypair, Signer};
+
+struct Balances {
+    player: u64,
+    promoter: u64,
+    vault: u64,
+    game: u64,
+}
+
+async fn balances(env: &mut Env, game: &TestGame) -> Balances {
+    let (player, promoter, vault) = (env.player.pubkey(), env.promoter.pubkey(), env.vault);
+
+    Balances {
+        player: env.lamports(&player).await,
+        promoter: env.lamports(&promoter).await,
+        vault: env.lamports(&vault).await,
+        game: env.lamports(&game.key).await,
+    }
+}
+
+/// Joins a game, checking its escrow, returns the vault balance before it.
+async fn escrowed(env: &mut Env, answer: u16, point: u16) -> (TestGame, u64) {
+    let a = amounts();
+    let vault_start = env.lamports(&env.vault.clone()).await;
+    let game = env.joined(answer, point).await;
+
+    let b = balances(env, &game).await;
+    assert_eq!(b.player, START_LAMPORTS - a.bet);
+    assert_eq!(b.promoter, START_LAMPORTS - a.collateral);
+    assert_eq!(b.game, a.collateral + a.bet);
+    assert_eq!(b.vault, vault_start);
+
+    (game, vault_start)
+}
+
+#[tokio::test]
+async fn player_win() {
+    let mut env = start().await;
+    let a = amounts();
+    let (game, vault_start) = escrowed(&mut env, 3, 3).await;
+
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    let b = balances(&mut env, &game).await;
+    assert_eq!(b.player, START_LAMPORTS - a.bet + a.win - a.fee);
+    assert_eq!(b.promoter, START_LAMPORTS - a.win + a.bet);
+    assert_eq!(b.vault, vault_start + a.fee);
+    assert_eq!(b.game, 0);
+}
+
+#[tokio::test]
+async fn promoter_win() {
+    let mut env = start().await;
+    let a = amounts();
+    let (game, vault_start) = escrowed(&mut env, 3, 5).await;
+
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    let b = balances(&mut env, &game).await;
+    assert_eq!(b.player, START_LAMPORTS - a.bet);
+    assert_eq!(b.promoter, START_LAMPORTS + a.bet - a.fee);
+    assert_eq!(b.vault, vault_start + a.fee);
+    assert_eq!(b.game, 0);
+}
+
+#[tokio::test]
+async fn promoter_no_reveal_timeout() {
+    let mut env = start().await;
+    let a = amounts();
+    let (game, vault_start) = escrowed(&mut env, 3, 5).await;
+
+    let result = env.end(&game).await;
+    assert_game_error(result, GameError::RevealPending);
+
+    env.warp(REVEAL_TIME + 1).await;
+    env.end(&game).await.unwrap();
+
+    let b = balances(&mut env, &game).await;
+    assert_eq!(b.player, START_LAMPORTS + a.win);
+    assert_eq!(b.promoter, START_LAMPORTS - a.collateral + a.rent);
+    assert_eq!(b.vault, vault_start + a.fee);
+    assert_eq!(b.game, 0);
+}
+
+#[tokio::test]
+async fn late_reveal_is_rejected() {
+    let mut env = start().await;
+    let a = amounts();
+    let (game, _) = escrowed(&mut env, 3, 3).await;
+
+    env.warp(REVEAL_TIME + 1).await;
+    let result = env.reveal(&game).await;
+    assert_game_error(result, GameError::RevealTimeExpired);
+
+    assert_eq!(env.lamports(&game.key).await, a.collateral + a.bet);
+    assert_eq!(env.record(&game.key).await.answer, 0);
+}
+
+#[tokio::test]
+async fn wrong_promoter_and_player_are_rejected() {
+    let mut env = start().await;
+    let a = amounts();
+
+    let intruder = Keypair::new();
+    env.context.set_account(&intruder.pubkey(), &system_account(START_LAMPORTS).into());
+    let (player, promoter) = (env.player.pubkey(), env.promoter.pubkey());
+    let player_keypair = env.player.insecure_clone();
+
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    let ix = instruction::participate(&player, &intruder.pubkey(), &game.key, 3);
+    let result = env.send(ix, &[&player_keypair]).await;
+    assert_game_error(result, GameError::PromoterMismatch);
+
+    env.participate(&game, 3).await.unwrap();
+
+    let params = reveal::Params { x: game.answer, secret: game.secret.clone() };
+    let ix = instruction::reveal(&intruder.pubkey(), &game.key, params);
+    let result = env.send(ix, &[&intruder]).await;
+    assert_game_error(result, GameError::NotPromoter);
+
+    env.reveal(&game).await.unwrap();
+
+    let ix = instruction::end(&player, &intruder.pubkey(), &player, &game.key);
+    let result = env.send(ix, &[&player_keypair]).await;
+    assert_game_error(result, GameError::PromoterMismatch);
+
+    let result = env.end_with(&intruder, &intruder.pubkey(), &game).await;
+    assert_game_error(result, GameError::PlayerMismatch);
+
+    let result = env.end_with(&intruder, &player, &game).await;
+    assert_game_error(result, GameError::InvalidSettler);
+
+    let ix = instruction::delete(&intruder.pubkey(), &game.key);
+    let result = env.send(ix, &[&intruder]).await;
+    assert_game_error(result, GameError::NotPromoter);
+
+    assert_eq!(env.lamports(&game.key).await, a.collateral + a.bet);
+    assert_eq!(env.lamports(&intruder.pubkey()).await, START_LAMPORTS);
+
+    let promoter_keypair = env.promoter.insecure_clone();
+    env.end_with(&promoter_keypair, &player, &game).await.unwrap();
+    assert_eq!(env.lamports(&promoter).await, START_LAMPORTS - a.win + a.bet);
+}
+
+#[tokio::test]
+async fn create_then_delete() {
+    let mut env = start().await;
+    let a = amounts();
+    let game = env.game(10, 3);
+    let vault_start = env.lamports(&env.vault.clone()).await;
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    assert_eq!(env.lamports(&game.key).await, a.collateral);
+
+    env.delete(&game).await.unwrap();
+
+    let b = balances(&mut env, &game).await;
+    assert_eq!(b.promoter, START_LAMPORTS);
+    assert_eq!(b.player, START_LAMPORTS);
+    assert_eq!(b.vault, vault_start);
+    assert_eq!(b.game, 0);
+}
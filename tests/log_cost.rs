@@ -0,0 +1,51 @@
+//! Compute units of each instruction with and without the `no-logs`
+//! feature, metered on both SBF builds of the program:
+//!
+//! ``` shell
+//! cargo build-sbf --sbf-out-dir target/deploy/logs
+//! cargo build-sbf --sbf-out-dir target/deploy/no-logs --features no-logs
+//! cargo test --test log_cost -- --ignored --nocapture
+//! ```
+
+mod common;
+
+use common::{config_params, sbf_build, start_sbf, LEVEL, ODDS_X100};
+use digital_sol_game::instruction;
+use solana_sdk::signature::Signer;
+use std::path::Path;
+
+/// Compute units of the game instructions, `Delete` and `UpdateConfig` on the build at `so`.
+async fn units(so: &Path) -> Vec<(&'static str, u64)> {
+    let mut env = start_sbf(so, |instruction| instruction).await;
+    let mut units = env.game_units().await;
+
+    let (admin, promoter) = (env.admin.insecure_clone(), env.promoter.insecure_clone());
+    let game = env.game(10, 3);
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    let ix = env.delete_ix(&game);
+    units.push(("Delete", env.send_for_units(ix, &[&promoter]).await));
+
+    let ix = instruction::update_config(&admin.pubkey(), config_params());
+    units.push(("UpdateConfig", env.send_for_units(ix, &[&admin]).await));
+
+    units
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds, see the module docs"]
+async fn logging_cost_per_instruction() {
+    let logs = units(&sbf_build("logs")).await;
+    let no_logs = units(&sbf_build("no-logs")).await;
+
+    for ((name, with_logs), (_, without_logs)) in logs.into_iter().zip(no_logs) {
+        println!(
+            "{:<14} logs {:>6} CU  no-logs {:>6} CU  saved {:>5} CU",
+            name,
+            with_logs,
+            without_logs,
+            with_logs.saturating_sub(without_logs),
+        );
+        // every one of them logs its steps with `msg!`
+        assert!(without_logs < with_logs, "{} spends as much without logs", name);
+    }
+}
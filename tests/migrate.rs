@@ -0,0 +1,156 @@
+mod common;
+
+use common::{start, Env, TestGame, FEE_BPS, LEVEL, ODDS_X100, REVEAL_TIME, START_LAMPORTS};
+use digital_sol_game::{
+    instruction,
+    settlement::{settle, Outcome},
+    state::game::{CommitmentScheme, GameRecord, GameStatus},
+};
+use solana_sdk::{account::Account, program_pack::Pack, rent::Rent, signature::Signer};
+
+/// The original 89 bytes layout, without header nor extension.
+fn v1_data(record: &GameRecord) -> Vec<u8> {
+    let mut data = Vec::with_capacity(GameRecord::V1_LEN);
+    data.extend_from_slice(record.promoter.as_ref());
+    data.extend_from_slice(record.player.as_ref());
+    data.extend_from_slice(&record.firing_point.to_le_bytes());
+    data.extend_from_slice(&record.max.to_le_bytes());
+    data.extend_from_slice(&record.odds_x100.to_le_bytes());
+    data.push(record.level as u8);
+    data.extend_from_slice(&record.shot_time.to_le_bytes());
+    data.extend_from_slice(&record.answer.to_le_bytes());
+    data.extend_from_slice(&record.random_string);
+    assert_eq!(data.len(), GameRecord::V1_LEN);
+    data
+}
+
+/// A v1 game joined by `env.player` at `point`, answered with `answer` if any.
+/// v1 had no separate rent, the whole `escrow` balance is paid out.
+async fn plant_v1(env: &mut Env, game: &TestGame, point: u16, answer: u16, escrow: u64) -> GameRecord {
+    let mut record = GameRecord::new(env.promoter.pubkey(), game.max, ODDS_X100, LEVEL, 0);
+    record.player = env.player.pubkey();
+    record.firing_point = point;
+    record.shot_time = env.now().await;
+    record.answer = answer;
+
+    let account = Account {
+        lamports: escrow,
+        data: v1_data(&record),
+        owner: digital_sol_game::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    env.context.set_account(&game.key, &account.into());
+
+    record
+}
+
+/// What a v1 game locked: the promoter's `pre_store` and the player's stake.
+fn escrow() -> u64 {
+    LEVEL.get_bet() * ODDS_X100 as u64 / 100 * 2
+}
+
+/// The rent `Migrate` adds for the bigger layout.
+fn top_up() -> u64 {
+    let rent = Rent::default();
+    rent.minimum_balance(GameRecord::LEN) - rent.minimum_balance(GameRecord::V1_LEN)
+}
+
+async fn migrate(env: &mut Env, game: &TestGame) {
+    let payer = env.admin.insecure_clone();
+    env.send(instruction::migrate(&payer.pubkey(), &game.key), &[&payer]).await.unwrap();
+}
+
+#[tokio::test]
+async fn migrated_game_keeps_its_funds_and_settles() {
+    let mut env = start().await;
+    let game = TestGame::legacy(10, 3, *b"abcdef");
+    let escrow = escrow();
+    let planted = plant_v1(&mut env, &game, 3, 0, escrow).await;
+
+    let top_up = top_up();
+    let (admin, before) = (env.admin.pubkey(), env.lamports(&game.key).await);
+    let admin_before = env.lamports(&admin).await;
+
+    migrate(&mut env, &game).await;
+    assert_eq!(env.lamports(&game.key).await, before + top_up);
+    assert_eq!(env.lamports(&admin).await, admin_before - top_up);
+
+    let record = env.record(&game.key).await;
+    assert_eq!(record.status, GameStatus::Joined);
+    assert_eq!(record.commitment, CommitmentScheme::Legacy);
+    assert_eq!(record.promoter, planted.promoter);
+    assert_eq!(record.player, planted.player);
+    assert_eq!(record.firing_point, 3);
+    assert_eq!(record.max, game.max);
+    assert_eq!(record.odds_x100, ODDS_X100);
+    assert_eq!(record.level, LEVEL);
+    assert_eq!(record.shot_time, planted.shot_time);
+    assert_eq!(record.stake(), LEVEL.get_bet());
+
+    env.reveal(&game).await.unwrap();
+    assert_eq!(env.record(&game.key).await.status, GameStatus::Revealed);
+
+    let (player, promoter, vault) = (env.player.pubkey(), env.promoter.pubkey(), env.vault);
+    let vault_before = env.lamports(&vault).await;
+    let settlement = settle(Outcome::PlayerWin, escrow, LEVEL.get_bet(), ODDS_X100, FEE_BPS).unwrap();
+
+    env.end(&game).await.unwrap();
+    assert_eq!(env.lamports(&player).await, START_LAMPORTS + settlement.to_player);
+    assert_eq!(env.lamports(&promoter).await, START_LAMPORTS + settlement.to_promoter + top_up);
+    assert_eq!(env.lamports(&vault).await, vault_before + settlement.to_vault);
+    assert_eq!(env.lamports(&game.key).await, 0);
+}
+
+#[tokio::test]
+async fn unrevealed_migrated_game_pays_the_player_the_whole_v1_balance() {
+    let mut env = start().await;
+    let game = TestGame::legacy(10, 3, *b"abcdef");
+    plant_v1(&mut env, &game, 3, 0, escrow()).await;
+    migrate(&mut env, &game).await;
+
+    let record = env.record(&game.key).await;
+    env.set_time(record.shot_time + REVEAL_TIME + 1).await;
+
+    let (player, promoter) = (env.player.pubkey(), env.promoter.pubkey());
+    let settlement = settle(Outcome::NoReveal, escrow(), LEVEL.get_bet(), ODDS_X100, FEE_BPS).unwrap();
+
+    env.end(&game).await.unwrap();
+    assert_eq!(env.lamports(&player).await, START_LAMPORTS + settlement.to_player);
+    assert_eq!(env.lamports(&promoter).await, START_LAMPORTS + top_up());
+    assert_eq!(env.lamports(&game.key).await, 0);
+}
+
+#[tokio::test]
+async fn out_of_range_v1_answer_settles_under_the_v1_rules() {
+    let mut env = start().await;
+    let game = TestGame::legacy(10, 11, *b"abcdef");
+    plant_v1(&mut env, &game, 3, 11, escrow()).await;
+    migrate(&mut env, &game).await;
+    assert_eq!(env.record(&game.key).await.status, GameStatus::Revealed);
+
+    let (player, promoter) = (env.player.pubkey(), env.promoter.pubkey());
+    let settlement = settle(Outcome::PromoterWin, escrow(), LEVEL.get_bet(), ODDS_X100, FEE_BPS).unwrap();
+
+    env.end(&game).await.unwrap();
+    assert_eq!(env.lamports(&player).await, START_LAMPORTS);
+    assert_eq!(env.lamports(&promoter).await, START_LAMPORTS + settlement.to_promoter + top_up());
+}
+
+#[tokio::test]
+async fn migration_recovers_the_status() {
+    let mut env = start().await;
+
+    for (point, answer, status) in [
+        (0, 0, GameStatus::Open),
+        (3, 0, GameStatus::Joined),
+        (3, 7, GameStatus::Revealed),
+        (3, 11, GameStatus::Revealed),
+    ] {
+        let game = env.game(10, 3);
+        plant_v1(&mut env, &game, point, answer, escrow()).await;
+
+        migrate(&mut env, &game).await;
+        assert_eq!(env.record(&game.key).await.status, status, "point {} answer {}", point, answer);
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{amounts, assert_game_error, start, system_account, LEVEL, ODDS_X100, START_LAMPORTS};
+use digital_sol_game::{
+    error::GameError,
+    state::game::GameStatus,
+};
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn participate_records_the_real_player() {
+    let mut env = start().await;
+    let game = env.joined(3, 3).await;
+
+    let record = env.record(&game.key).await;
+    assert_eq!(record.player, env.player.pubkey());
+    assert_eq!(record.firing_point, 3);
+    assert_eq!(record.status, GameStatus::Joined);
+}
+
+#[tokio::test]
+async fn real_player_receives_winnings() {
+    let mut env = start().await;
+    let game = env.joined(3, 3).await;
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    let a = amounts();
+    let player_key = env.player.pubkey();
+    assert_eq!(env.lamports(&player_key).await, START_LAMPORTS - a.bet + a.win - a.fee);
+}
+
+#[tokio::test]
+async fn second_participant_is_rejected() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    let intruder = Keypair::new();
+    env.context.set_account(&intruder.pubkey(), &system_account(START_LAMPORTS).into());
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+    env.participate(&game, 3).await.unwrap();
+
+    let result = env.participate_as(&intruder, &game, 4).await;
+    assert_game_error(result, GameError::GameAlreadyStarted);
+
+    let record = env.record(&game.key).await;
+    assert_eq!(record.player, env.player.pubkey());
+}
+
+#[tokio::test]
+async fn promoter_cannot_join_own_game() {
+    let mut env = start().await;
+    let game = env.game(10, 3);
+
+    env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.participate_as(&promoter, &game, 3).await;
+    assert_game_error(result, GameError::PromoterCannotPlay);
+}
+
+#[tokio::test]
+async fn end_rejects_a_different_player_account() {
+    let mut env = start().await;
+    let game = env.joined(3, 3).await;
+    env.reveal(&game).await.unwrap();
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.end_with(&promoter, &Keypair::new().pubkey(), &game).await;
+    assert_game_error(result, GameError::PlayerMismatch);
+}
@@ -0,0 +1,46 @@
+//! Compute units of each game instruction before and after the game bump
+//! was stored and the config and vault keys precomputed, metered on the SBF
+//! builds of both revisions. The `before-pda-bump` tag marks the last one
+//! searching the game, config and vault keys, it's built with the current
+//! lockfile:
+//!
+//! ``` shell
+//! git worktree add ../pda-before before-pda-bump
+//! cp Cargo.lock ../pda-before/
+//! cargo build-sbf --manifest-path ../pda-before/Cargo.toml --sbf-out-dir target/deploy/pda-before
+//! cargo build-sbf --sbf-out-dir target/deploy/pda-after
+//! cargo test --test pda_cost -- --ignored --nocapture
+//! ```
+
+mod common;
+
+use common::{sbf_build, start_sbf};
+use digital_sol_game::instruction::{INSTRUCTION_VERSION, VERSION_PREFIX};
+use solana_sdk::instruction::Instruction;
+
+/// The previous revision reads the game instructions as version 1, their
+/// layouts haven't changed since.
+fn version_1(mut instruction: Instruction) -> Instruction {
+    assert_eq!(instruction.data[..2], [VERSION_PREFIX, INSTRUCTION_VERSION]);
+    instruction.data[1] = 1;
+    instruction
+}
+
+#[tokio::test]
+#[ignore = "needs the SBF builds, see the module docs"]
+async fn pda_cost_per_instruction() {
+    let before = start_sbf(&sbf_build("pda-before"), version_1).await.game_units().await;
+    let after = start_sbf(&sbf_build("pda-after"), |instruction| instruction).await.game_units().await;
+
+    for ((name, before), (_, after)) in before.into_iter().zip(after) {
+        println!(
+            "{:<12} before {:>6} CU  after {:>6} CU  saved {:>5} CU",
+            name,
+            before,
+            after,
+            before.saturating_sub(after),
+        );
+        // each of them searched the config key with `find_program_address`
+        assert!(after < before, "{} spends as much with the precomputed keys", name);
+    }
+}
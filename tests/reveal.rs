@@ -0,0 +1,98 @@
+mod common;
+
+use common::{amounts, assert_game_error, start};
+use digital_sol_game::{
+    error::GameError,
+    state::game::{GameRecord, GameStatus},
+};
+use solana_sdk::{program_pack::Pack, rent::Rent, signature::Signer};
+
+#[tokio::test]
+async fn answers_on_the_range_bounds_are_revealed() {
+    for answer in [1, 10] {
+        let mut env = start().await;
+        let game = env.joined(answer, 5).await;
+
+        env.reveal(&game).await.unwrap();
+
+        let record = env.record(&game.key).await;
+        assert_eq!(record.status, GameStatus::Revealed);
+        assert_eq!(record.answer, answer);
+    }
+}
+
+#[tokio::test]
+async fn out_of_range_answers_are_forfeits() {
+    for answer in [0, 11, u16::MAX] {
+        let mut env = start().await;
+        let game = env.joined(answer, 5).await;
+
+        env.reveal(&game).await.unwrap();
+
+        let record = env.record(&game.key).await;
+        assert_eq!(record.status, GameStatus::Forfeited);
+        assert_eq!(record.answer, answer);
+    }
+}
+
+#[tokio::test]
+async fn forfeit_pays_the_player_the_full_escrow() {
+    let mut env = start().await;
+    let game = env.joined(0, 5).await;
+    env.reveal(&game).await.unwrap();
+
+    let player_key = env.player.pubkey();
+    let promoter_key = env.promoter.pubkey();
+    let escrow = env.lamports(&game.key).await;
+    let player_before = env.lamports(&player_key).await;
+    let promoter_before = env.lamports(&promoter_key).await;
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+
+    env.end(&game).await.unwrap();
+
+    let rent = Rent::default().minimum_balance(GameRecord::LEN);
+    assert_eq!(env.lamports(&player_key).await, player_before + escrow - rent);
+    assert_eq!(env.lamports(&promoter_key).await, promoter_before + rent);
+    assert_eq!(env.lamports(&vault).await, vault_before);
+}
+
+#[tokio::test]
+async fn wrong_guess_pays_the_promoter() {
+    let mut env = start().await;
+    let game = env.joined(3, 5).await;
+    env.reveal(&game).await.unwrap();
+
+    let promoter_key = env.promoter.pubkey();
+    let escrow = env.lamports(&game.key).await;
+    let promoter_before = env.lamports(&promoter_key).await;
+    let vault = env.vault;
+    let vault_before = env.lamports(&vault).await;
+
+    env.end(&game).await.unwrap();
+
+    let fee = amounts().fee;
+    assert_eq!(env.lamports(&promoter_key).await, promoter_before + escrow - fee);
+    assert_eq!(env.lamports(&vault).await, vault_before + fee);
+}
+
+#[tokio::test]
+async fn answer_cannot_be_revealed_twice() {
+    for answer in [3, 0] {
+        let mut env = start().await;
+        let game = env.joined(answer, 5).await;
+        env.reveal(&game).await.unwrap();
+
+        let result = env.reveal_with(&game, answer, game.secret.clone()).await;
+        assert_game_error(result, GameError::AlreadyRevealed);
+    }
+}
+
+#[tokio::test]
+async fn settlement_waits_for_the_reveal() {
+    let mut env = start().await;
+    let game = env.joined(3, 5).await;
+
+    let result = env.end(&game).await;
+    assert_game_error(result, GameError::RevealPending);
+}
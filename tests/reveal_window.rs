@@ -0,0 +1,180 @@
+//! Reveal window boundaries, the window comes from the `Config`.
+
+mod common;
+
+use common::{
+    amounts, assert_game_error, config_params, start, Env, TestGame, LEVEL, MAX_REVEAL_TIME, MIN_REVEAL_TIME,
+    ODDS_X100, REVEAL_TIME, START_LAMPORTS,
+};
+use digital_sol_game::{
+    error::GameError,
+    state::game::{CommitmentScheme, GameRecord, GameStatus},
+};
+use solana_sdk::signature::Signer;
+
+/// Offsets from the reveal deadline fixed when the player joins.
+const OFFSETS: [i64; 3] = [-1, 0, 1];
+
+/// Reveal deadline of the game, fixed when the player joined.
+async fn deadline(env: &mut Env, game: &TestGame) -> i64 {
+    let record = env.record(&game.key).await;
+    assert_eq!(record.reveal_deadline, record.shot_time + REVEAL_TIME);
+    record.reveal_deadline
+}
+
+#[tokio::test]
+async fn reveal_is_accepted_until_the_deadline() {
+    for offset in OFFSETS {
+        let mut env = start().await;
+        let game = env.joined(3, 3).await;
+        let deadline = deadline(&mut env, &game).await;
+
+        env.set_time(deadline + offset).await;
+        let result = env.reveal(&game).await;
+
+        if offset <= 0 {
+            result.unwrap();
+            assert_eq!(env.record(&game.key).await.status, GameStatus::Revealed);
+        } else {
+            assert_game_error(result, GameError::RevealTimeExpired);
+            assert_eq!(env.record(&game.key).await.status, GameStatus::Joined);
+        }
+    }
+}
+
+#[tokio::test]
+async fn unrevealed_game_settles_only_after_the_deadline() {
+    let a = amounts();
+
+    for offset in OFFSETS {
+        let mut env = start().await;
+        let game = env.joined(3, 5).await;
+        let deadline = deadline(&mut env, &game).await;
+
+        env.set_time(deadline + offset).await;
+        let result = env.end(&game).await;
+
+        if offset <= 0 {
+            // (in time, not revealed)
+            assert_game_error(result, GameError::RevealPending);
+        } else {
+            // (over time, not revealed): the player takes the escrow minus the fee
+            result.unwrap();
+            let player_key = env.player.pubkey();
+            assert_eq!(env.lamports(&player_key).await, START_LAMPORTS + a.win);
+        }
+    }
+}
+
+#[tokio::test]
+async fn revealed_game_settles_on_both_sides_of_the_deadline() {
+    let a = amounts();
+
+    for offset in OFFSETS {
+        let mut env = start().await;
+        let game = env.joined(3, 3).await;
+        let deadline = deadline(&mut env, &game).await;
+        env.reveal(&game).await.unwrap();
+
+        // (in time, revealed) and (over time, revealed) pay the same
+        env.set_time(deadline + offset).await;
+        env.end(&game).await.unwrap();
+
+        let player_key = env.player.pubkey();
+        assert_eq!(env.lamports(&player_key).await, START_LAMPORTS - a.bet + a.win - a.fee);
+    }
+}
+
+#[tokio::test]
+async fn forfeit_settles_on_both_sides_of_the_deadline() {
+    for offset in OFFSETS {
+        let mut env = start().await;
+        let game = env.game(10, 0);
+        env.create(&game, ODDS_X100, LEVEL).await.unwrap();
+        env.participate(&game, 3).await.unwrap();
+        env.reveal(&game).await.unwrap();
+
+        let deadline = env.record(&game.key).await.reveal_deadline;
+        env.set_time(deadline + offset).await;
+        env.end(&game).await.unwrap();
+
+        assert_eq!(env.lamports(&game.key).await, 0);
+    }
+}
+
+#[tokio::test]
+async fn game_reveal_time_sets_the_deadline() {
+    let reveal_time = MIN_REVEAL_TIME * 2;
+
+    for offset in OFFSETS {
+        let mut env = start().await;
+        let game = env.game(10, 3);
+        env.create_with_reveal_time(&game, ODDS_X100, LEVEL, reveal_time).await.unwrap();
+        assert_eq!(env.record(&game.key).await.reveal_time, reveal_time);
+        assert_eq!(env.record(&game.key).await.reveal_deadline, 0);
+
+        env.participate(&game, 3).await.unwrap();
+        let record = env.record(&game.key).await;
+        assert_eq!(record.reveal_deadline, record.shot_time + reveal_time);
+
+        env.set_time(record.reveal_deadline + offset).await;
+        let result = env.reveal(&game).await;
+
+        if offset <= 0 {
+            result.unwrap();
+        } else {
+            assert_game_error(result, GameError::RevealTimeExpired);
+        }
+    }
+}
+
+#[tokio::test]
+async fn reveal_time_outside_the_config_bounds_is_rejected() {
+    let mut env = start().await;
+
+    for reveal_time in [-1, MIN_REVEAL_TIME - 1, MAX_REVEAL_TIME + 1, i64::MAX] {
+        let game = env.game(10, 3);
+        let result = env.create_with_reveal_time(&game, ODDS_X100, LEVEL, reveal_time).await;
+        assert_game_error(result, GameError::InvalidRevealTime);
+    }
+
+    for reveal_time in [MIN_REVEAL_TIME, MAX_REVEAL_TIME] {
+        let game = env.game(10, 3);
+        env.create_with_reveal_time(&game, ODDS_X100, LEVEL, reveal_time).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn config_update_does_not_move_a_joined_deadline() {
+    let mut env = start().await;
+    let game = env.joined(3, 3).await;
+        let deadline = deadline(&mut env, &game).await;
+
+    let mut params = config_params();
+    params.reveal_time = MIN_REVEAL_TIME;
+    env.update_config(params).await.unwrap();
+
+    env.set_time(deadline).await;
+    env.reveal(&game).await.unwrap();
+}
+
+#[tokio::test]
+async fn legacy_joined_game_counts_from_the_shot_time() {
+    let mut env = start().await;
+    let game = TestGame::legacy(10, 3, *b"abcdef");
+    let now = env.now().await;
+
+    let mut record = GameRecord::new(env.promoter.pubkey(), game.max, ODDS_X100, LEVEL, 0);
+    record.commitment = CommitmentScheme::Legacy;
+    record.player = env.player.pubkey();
+    record.firing_point = 3;
+    record.shot_time = now;
+    record.status = GameStatus::Joined;
+    env.set_record(&game.key, &record, 1_000_000_000);
+
+    env.set_time(now + REVEAL_TIME + 1).await;
+    assert_game_error(env.reveal(&game).await, GameError::RevealTimeExpired);
+
+    env.set_time(now + REVEAL_TIME).await;
+    env.reveal(&game).await.unwrap();
+}
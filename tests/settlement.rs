@@ -0,0 +1,119 @@
+use digital_sol_game::{
+    error::GameError,
+    settlement::{check_solvency, crank_reward, required_collateral, settle, win_amount, Outcome, Settlement},
+    state::game::GameLevel,
+};
+
+const LEVELS: [GameLevel; 5] = [GameLevel::S, GameLevel::A, GameLevel::B, GameLevel::C, GameLevel::D];
+const ODDS: [u32; 5] = [0, 1, 100, 200, u32::MAX];
+const RENT: u64 = 2_000_000;
+const FEES: [u16; 4] = [0, 100, 9_999, 10_000];
+const OUTCOMES: [Outcome; 4] = [Outcome::PlayerWin, Outcome::PromoterWin, Outcome::NoReveal, Outcome::Forfeit];
+
+#[test]
+fn settlement_always_sums_to_the_escrow() {
+    for level in LEVELS {
+        let bet = level.get_bet();
+        for odds in ODDS {
+            for fee_bps in FEES {
+                let escrow = match required_collateral(bet, odds, fee_bps, RENT) {
+                    Ok(collateral) => collateral - RENT + bet,
+                    Err(err) => {
+                        assert_eq!(err, GameError::MathOverflow);
+                        continue;
+                    }
+                };
+
+                for outcome in OUTCOMES {
+                    let s = settle(outcome, escrow, bet, odds, fee_bps).unwrap();
+                    assert_eq!(
+                        s.to_player + s.to_promoter + s.to_vault,
+                        escrow,
+                        "{:?} {:?} odds {} fee {}", level, outcome, odds, fee_bps,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn collateral_is_solvent_under_a_lower_fee() {
+    for level in LEVELS {
+        let bet = level.get_bet();
+        for odds in ODDS {
+            for fee_bps in FEES {
+                let Ok(collateral) = required_collateral(bet, odds, fee_bps, RENT) else { continue };
+                let escrow = collateral - RENT + bet;
+
+                assert_eq!(check_solvency(escrow, bet, odds, fee_bps), Ok(()));
+                assert_eq!(check_solvency(escrow, bet, odds, 0), Ok(()));
+                assert_eq!(check_solvency(escrow - 1, bet, odds, fee_bps), Err(GameError::InsufficientEscrow));
+            }
+        }
+    }
+}
+
+#[test]
+fn huge_odds_overflow_instead_of_wrapping() {
+    let bet = GameLevel::S.get_bet();
+    assert_eq!(win_amount(bet, u32::MAX), Ok(bet / 100 * u32::MAX as u64));
+    assert_eq!(win_amount(u64::MAX, u32::MAX), Err(GameError::MathOverflow));
+    assert_eq!(required_collateral(u64::MAX, 100, 0, 1), Err(GameError::MathOverflow));
+    assert_eq!(check_solvency(u64::MAX, u64::MAX, 100, 0), Err(GameError::MathOverflow));
+}
+
+#[test]
+fn collateral_is_the_win_plus_fee_plus_rent() {
+    let bet = GameLevel::D.get_bet();
+    let win = bet * 2;
+    assert_eq!(required_collateral(bet, 200, 100, RENT), Ok(win + win / 100 + RENT));
+}
+
+#[test]
+fn split_of_each_outcome() {
+    let bet = 1_000;
+    let escrow = required_collateral(bet, 200, 100, 0).unwrap() + bet;
+    let win = 2_000;
+    let fee = 20;
+
+    let split = |outcome| settle(outcome, escrow, bet, 200, 100).unwrap();
+    assert_eq!(
+        split(Outcome::PlayerWin),
+        Settlement { to_player: win - fee, to_promoter: escrow - win, to_vault: fee },
+    );
+    assert_eq!(
+        split(Outcome::PromoterWin),
+        Settlement { to_player: 0, to_promoter: escrow - fee, to_vault: fee },
+    );
+    assert_eq!(
+        split(Outcome::NoReveal),
+        Settlement { to_player: escrow - fee, to_promoter: 0, to_vault: fee },
+    );
+    assert_eq!(
+        split(Outcome::Forfeit),
+        Settlement { to_player: escrow, to_promoter: 0, to_vault: 0 },
+    );
+}
+
+#[test]
+fn short_escrow_is_rejected() {
+    let bet = 1_000;
+    assert_eq!(
+        settle(Outcome::PlayerWin, 1_999, bet, 200, 0),
+        Err(GameError::InsufficientEscrow),
+    );
+}
+
+#[test]
+fn crank_reward_never_exceeds_the_vault_fee() {
+    for fee in [0, 1, 20_000, u64::MAX] {
+        for crank_reward_bps in FEES {
+            let reward = crank_reward(fee, crank_reward_bps).unwrap();
+            assert!(reward <= fee);
+        }
+    }
+
+    assert_eq!(crank_reward(20_000, 1_000), Ok(2_000));
+    assert_eq!(crank_reward(u64::MAX, 10_000), Ok(u64::MAX));
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use common::{amounts, assert_game_error, start, Env};
+use digital_sol_game::{error::GameError, state::vault::Vault};
+use solana_sdk::{
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+};
+
+/// Plays a game the promoter wins, returning the fee it paid into the vault.
+async fn collect_fee(env: &mut Env) -> u64 {
+    let game = env.joined(3, 5).await;
+    env.reveal(&game).await.unwrap();
+    env.end(&game).await.unwrap();
+
+    amounts().fee
+}
+
+#[tokio::test]
+async fn fees_are_collected_into_the_vault_pda() {
+    let mut env = start().await;
+    let fee = collect_fee(&mut env).await;
+
+    let vault = env.vault;
+    let rent = Rent::default().minimum_balance(Vault::LEN);
+    assert_eq!(env.lamports(&vault).await, rent + fee);
+
+    let state = env.vault_state().await;
+    assert_eq!(state.fees_collected, fee);
+    assert_eq!(state.fees_withdrawn, 0);
+    assert_eq!(state.signers(), &[env.admin.pubkey()]);
+}
+
+#[tokio::test]
+async fn admin_withdraws_until_another_authority_is_set() {
+    let mut env = start().await;
+    let fee = collect_fee(&mut env).await;
+    let destination = env.promoter.pubkey();
+    let destination_before = env.lamports(&destination).await;
+
+    let admin = env.admin.insecure_clone();
+    env.withdraw_fees(&[&admin], &destination, fee).await.unwrap();
+
+    assert_eq!(env.lamports(&destination).await, destination_before + fee);
+    assert_eq!(env.vault_state().await.fees_withdrawn, fee);
+}
+
+#[tokio::test]
+async fn withdrawals_need_the_authority_and_keep_the_rent() {
+    let mut env = start().await;
+    let fee = collect_fee(&mut env).await;
+    let destination = Pubkey::new_unique();
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.withdraw_fees(&[&promoter], &destination, fee).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+
+    let admin = env.admin.insecure_clone();
+    let result = env.withdraw_fees(&[&admin], &destination, fee + 1).await;
+    assert_game_error(result, GameError::InsufficientVaultBalance);
+}
+
+#[tokio::test]
+async fn m_of_n_authority_needs_threshold_signatures() {
+    let mut env = start().await;
+    let fee = collect_fee(&mut env).await;
+    let destination = env.promoter.pubkey();
+    let destination_before = env.lamports(&destination).await;
+
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let keys = members.iter().map(|m| m.pubkey()).collect::<Vec<_>>();
+
+    let admin = env.admin.insecure_clone();
+    env.set_withdraw_authority_as(&[&admin], keys, 2).await.unwrap();
+
+    let result = env.withdraw_fees(&[&members[0]], &destination, fee).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+
+    let result = env.withdraw_fees(&[&admin], &destination, fee).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+
+    env.withdraw_fees(&[&members[0], &members[2]], &destination, fee).await.unwrap();
+    assert_eq!(env.lamports(&destination).await, destination_before + fee);
+}
+
+#[tokio::test]
+async fn admin_alone_cant_replace_an_m_of_n_authority() {
+    let mut env = start().await;
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let keys = members.iter().map(|m| m.pubkey()).collect::<Vec<_>>();
+
+    let admin = env.admin.insecure_clone();
+    env.set_withdraw_authority_as(&[&admin], keys.clone(), 2).await.unwrap();
+
+    let result = env.set_withdraw_authority_as(&[&admin], vec![admin.pubkey()], 1).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+
+    let result = env.set_withdraw_authority_as(&[&admin, &members[0]], vec![admin.pubkey()], 1).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+    assert_eq!(env.vault_state().await.signers(), keys.as_slice());
+
+    env.set_withdraw_authority_as(&[&members[1], &members[2]], vec![admin.pubkey()], 1).await.unwrap();
+    assert_eq!(env.vault_state().await.signers(), &[admin.pubkey()]);
+}
+
+#[tokio::test]
+async fn only_the_authority_sets_a_valid_authority() {
+    let mut env = start().await;
+    let keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let promoter = env.promoter.insecure_clone();
+    let result = env.set_withdraw_authority_as(&[&promoter], keys.clone(), 1).await;
+    assert_game_error(result, GameError::InsufficientSigners);
+
+    let admin = env.admin.insecure_clone();
+    for (signers, threshold) in [
+        (keys.clone(), 0),
+        (keys.clone(), 3),
+        (vec![], 1),
+        (vec![keys[0], keys[0]], 1),
+        (vec![Pubkey::new_unique(); 6], 1),
+    ] {
+        let result = env.set_withdraw_authority_as(&[&admin], signers, threshold).await;
+        assert_game_error(result, GameError::InvalidWithdrawAuthority);
+    }
+}
+
+#[test]
+fn short_destination_is_left_untouched() {
+    let vault = Vault::new(&[Pubkey::new_unique()], 1).unwrap();
+    let mut data = vec![0xaa; Vault::LEN - 1];
+
+    assert_eq!(vault.try_pack_into_slice(&mut data), Err(ProgramError::AccountDataTooSmall));
+    assert!(data.iter().all(|b| *b == 0xaa));
+}